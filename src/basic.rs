@@ -1,10 +1,15 @@
+use std::collections::HashSet;
+
 use svg::node::element::path::Data;
-use svg::Document;
-use svg::node::element::{Circle, Path};
+use svg::node::element::tag::Type;
+use svg::parser::Event;
+use svg::{Document, Node};
+use svg::node::element::{Circle, Group, Path};
 
+use crate::bounds::{self, BBox};
 use crate::ctx::Context;
 use crate::pord::{Cartesian, PordOrCord};
-use crate::utils::SweepDirection;
+use crate::utils::{self, LargeArcFlag, SweepDirection};
 
 pub fn circle(doc:Document, center:&PordOrCord, radius:f32, ctx:&Context) -> Document {
     let center = center.abs_svg_xy(ctx.origin());
@@ -95,4 +100,45 @@ pub fn arc_path(doc:Document,thickness:f32, start:&PordOrCord, end:&PordOrCord,r
         .set("stroke", ctx.colour().stroke())
         .set("stroke-width", ctx.stroke().strokewidth());
     doc.add(arc)
-}
\ No newline at end of file
+}
+//what circle would cover
+pub fn circle_bounds(center:&PordOrCord, radius:f32, ctx:&Context) -> BBox {
+    BBox::around(center.abs_svg_xy(ctx.origin()).into(), radius + ctx.stroke().strokewidth()/2.0)
+}
+
+//what arc_big_circle & arc_small_circle would cover, large picks between them
+pub fn arc_bounds(start:&PordOrCord, end:&PordOrCord, radius:f32, large:LargeArcFlag, sweep_dir:SweepDirection, ctx:&Context) -> BBox {
+    let start = start.abs_svg_xy(ctx.origin()).into();
+    let end = end.abs_svg_xy(ctx.origin()).into();
+    let points = utils::flatten_arc(start, end, radius, large, sweep_dir, bounds::TOLERANCE);
+    BBox::of_points(std::iter::once(start).chain(points), ctx.stroke().strokewidth()/2.0 + bounds::TOLERANCE)
+        .expect("there's always the start")
+}
+
+//what arc_path would cover
+pub fn arc_path_bounds(thickness:f32, start:&PordOrCord, end:&PordOrCord, radius:f32, sweep_dir:SweepDirection, ctx:&Context) -> BBox {
+    let outer = arc_bounds(start, end, radius + thickness, LargeArcFlag(false), sweep_dir, ctx);
+    let inner = arc_bounds(end, start, radius - thickness, LargeArcFlag(false), SweepDirection(!sweep_dir.0), ctx);
+    outer.union(&inner)
+}
+
+//ids already in the document, so new ones can keep clear of them
+pub fn used_ids(doc:&Document) -> HashSet<String> {
+    let content = doc.to_string();
+    let Ok(events) = svg::read(&content) else {
+        return HashSet::new()
+    };
+    events.filter_map(|event| match event {
+        Event::Tag(_, Type::Start | Type::Empty, attrs) => attrs.get("id").map(|id| id.to_string()),
+        _ => None,
+    }).collect()
+}
+
+//moves everything added to the document since `mark` children ago into the group
+pub fn group_since(mut doc:Document, mark:usize, mut group:Group) -> Document {
+    let nodes = doc.get_children_mut().split_off(mark);
+    for node in nodes {
+        group.append(node);
+    }
+    doc.add(group)
+}
@@ -0,0 +1,145 @@
+use std::io::Error;
+
+use svg::Document;
+use svg::node::Node;
+use svg::node::element::Element;
+use svg::node::element::path::Data;
+use svg::node::element::tag::Type;
+use svg::parser::Event;
+
+use crate::BACKGROUND_ID;
+use crate::plotter::sub_paths;
+use crate::utils::SvgPosition;
+
+//how closely curves are followed when measuring them, the boxes get this much extra so nothing's cut off
+pub(crate) const TOLERANCE :f32 = 0.05;
+
+//an axis aligned box in svg units, strokes included
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BBox {
+    min:SvgPosition,
+    max:SvgPosition,
+}
+
+//anything that knows how much of the page it'll cover once drawn
+pub trait Bounded {
+    fn bounding_box(&self) -> BBox;
+}
+
+impl BBox {
+    pub fn new(corner:SvgPosition, other:SvgPosition) -> BBox {
+        BBox {
+            min: SvgPosition(corner.0.min(other.0), corner.1.min(other.1)),
+            max: SvgPosition(corner.0.max(other.0), corner.1.max(other.1)),
+        }
+    }
+    //a square reaching pad either side of the point, so a circle's box when pad is its radius
+    pub fn around(point:SvgPosition, pad:f32) -> BBox {
+        BBox::new(SvgPosition(point.0 - pad, point.1 - pad), SvgPosition(point.0 + pad, point.1 + pad))
+    }
+    //None if there aren't any points
+    pub fn of_points(points:impl IntoIterator<Item = SvgPosition>, pad:f32) -> Option<BBox> {
+        points.into_iter()
+            .map(|point| BBox::around(point, pad))
+            .reduce(|a, b| a.union(&b))
+    }
+    pub fn min(&self) -> SvgPosition {
+        self.min
+    }
+    pub fn max(&self) -> SvgPosition {
+        self.max
+    }
+    pub fn width(&self) -> f32 {
+        self.max.0 - self.min.0
+    }
+    pub fn height(&self) -> f32 {
+        self.max.1 - self.min.1
+    }
+    pub fn centre(&self) -> SvgPosition {
+        SvgPosition((self.min.0 + self.max.0)/2.0, (self.min.1 + self.max.1)/2.0)
+    }
+    pub fn union(&self, other:&BBox) -> BBox {
+        BBox {
+            min: SvgPosition(self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: SvgPosition(self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+    pub fn grow(&self, by:f32) -> BBox {
+        BBox::new(SvgPosition(self.min.0 - by, self.min.1 - by), SvgPosition(self.max.0 + by, self.max.1 + by))
+    }
+    //(x, y, width, height), as a viewBox wants it
+    pub fn view_box(&self) -> (f32,f32,f32,f32) {
+        (self.min.0, self.min.1, self.width(), self.height())
+    }
+}
+
+//everything drawn in the document, from the svg itself so basic shapes & hand added nodes count too.
+//the background & anything that's neither stroked nor filled are left out, None if that's all there is
+pub fn measure(doc:&Document) -> Result<Option<BBox>, Error> {
+    let content = doc.to_string();
+    let mut bounds: Option<BBox> = None;
+    for event in svg::read(&content)? {
+        let (tag, attrs) = match event {
+            Event::Error(err) => return Err(Error::other(err)),
+            Event::Tag(tag @ ("circle" | "line" | "path"), Type::Start | Type::Empty, attrs) => (tag, attrs),
+            _ => continue,
+        };
+        let get = |name:&str| attrs.get(name).map(|value| value.to_string());
+        let num = |name:&str| get(name).and_then(|value| value.parse::<f32>().ok()).unwrap_or(0.0);
+        if get("id").as_deref() == Some(BACKGROUND_ID) {
+            continue
+        }
+        let stroked = get("stroke").is_some_and(|colour| colour != "none");
+        //svg fills in black unless told otherwise
+        if !stroked && get("fill").as_deref() == Some("none") {
+            continue
+        }
+        let pad = if stroked {
+            get("stroke-width").and_then(|width| width.parse::<f32>().ok()).unwrap_or(1.0)/2.0
+        } else {0.0};
+        let found = match tag {
+            "circle" => Some(BBox::around(SvgPosition(num("cx"), num("cy")), num("r") + pad)),
+            "line" => BBox::of_points([SvgPosition(num("x1"), num("y1")), SvgPosition(num("x2"), num("y2"))], pad),
+            _ => {
+                let data = Data::parse(&get("d").unwrap_or_default()).map_err(Error::other)?;
+                let points = sub_paths(&data).iter().flat_map(|path| path.flatten(TOLERANCE)).flatten().collect::<Vec<_>>();
+                BBox::of_points(points, pad + TOLERANCE)
+            }
+        };
+        bounds = match (bounds, found) {
+            (Some(a), Some(b)) => Some(a.union(&b)),
+            (a, b) => a.or(b),
+        };
+    }
+    Ok(bounds)
+}
+
+//sets the viewBox to just fit what's drawn, margin to spare on every side.
+//the background is stretched to match, an empty drawing is left as it is
+pub fn fit_view_box(mut doc:Document, margin:f32) -> Result<Document, Error> {
+    let Some(bounds) = measure(&doc)? else {
+        return Ok(doc)
+    };
+    let (x, y, width, height) = bounds.grow(margin).view_box();
+    doc.assign("viewBox", (x, y, width, height));
+    for child in doc.get_children_mut().iter_mut() {
+        //children are only reachable as text, so the background gets rebuilt from it
+        let text = child.to_string();
+        let Some(Event::Tag(tag, _, attrs)) = svg::read(&text)?.next() else {
+            continue
+        };
+        if attrs.get("id").map(|id| id.to_string()).as_deref() != Some(BACKGROUND_ID) {
+            continue
+        }
+        let mut background = Element::new(tag);
+        for (name, value) in attrs {
+            background.assign(name, value);
+        }
+        background.assign("x", x);
+        background.assign("y", y);
+        background.assign("width", width);
+        background.assign("height", height);
+        *child = Box::new(background);
+    }
+    Ok(doc)
+}
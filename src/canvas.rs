@@ -0,0 +1,170 @@
+use std::io::Error;
+
+use svg::{Document, Node};
+use svg::node::element::{Definitions, Description, Image, LinearGradient, RadialGradient, Rectangle, Stop, Title};
+
+use crate::BACKGROUND_ID;
+
+const GRADIENT_ID :&str = "background-fill";
+const TITLE_ID :&str = "svg-title";
+const DESC_ID :&str = "svg-desc";
+
+//gradient stops are (offset from 0 to 1, colour)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Transparent,
+    Solid(String),
+    //angle is like a POrd's, 0 runs top to bottom
+    LinearGradient{angle:f32, stops:Vec<(f32,String)>},
+    //from the middle out to the corners
+    RadialGradient(Vec<(f32,String)>),
+    //a path, url or data uri, scaled to cover the canvas
+    Image(String),
+}
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    width:f32,
+    height:f32,
+    margin:f32,
+    background:Background,
+    title:Option<String>,
+    desc:Option<String>,
+}
+
+impl Background {
+    pub fn solid(colour:&str) -> Background {
+        Background::Solid(colour.to_string())
+    }
+}
+
+impl Canvas {
+    pub fn new(width:f32, height:f32) -> Canvas {
+        Canvas { width, height, margin: 0.0, background: Background::solid("white"), title: None, desc: None }
+    }
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+    pub fn margin(&self) -> f32 {
+        self.margin
+    }
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+    //empty space around the drawing area, the background still covers it
+    pub fn set_margin(&mut self, margin:f32) {
+        self.margin = margin;
+    }
+    pub fn set_background(&mut self, background:Background) {
+        self.background = background;
+    }
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+    pub fn desc(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+    //what screen readers announce the drawing as, usually the text it was written from
+    pub fn set_title(&mut self, title:&str) {
+        self.title = Some(title.to_string());
+    }
+    //a longer description read after the title
+    pub fn set_desc(&mut self, desc:&str) {
+        self.desc = Some(desc.to_string());
+    }
+    //the middle of the drawing area, in svg units
+    pub fn centre(&self) -> (f32,f32) {
+        (self.width/2.0, self.height/2.0)
+    }
+    pub fn build(&self) -> (Document, (f32,f32)) {
+        //0 - so no margin gives 0 rather than -0
+        let (x, y) = (0.0 - self.margin, 0.0 - self.margin);
+        let (width, height) = (self.width + 2.0*self.margin, self.height + 2.0*self.margin);
+        let mut doc = Document::new().set("viewBox", (x, y, width, height));
+        //title & desc have to come first to be picked up
+        let mut labels = Vec::new();
+        if let Some(title) = &self.title {
+            doc = doc.add(Title::new(title.as_str()).set("id", TITLE_ID));
+            labels.push(TITLE_ID);
+        }
+        if let Some(desc) = &self.desc {
+            doc = doc.add(Description::new().set("id", DESC_ID).add(svg::node::Text::new(desc.as_str())));
+            labels.push(DESC_ID);
+        }
+        //no role="img" here, that would hide the word labels inside from screen readers
+        if !labels.is_empty() {
+            doc = doc.set("aria-labelledby", labels.join(" "));
+        }
+        let rect = || Rectangle::new()
+            .set("id", BACKGROUND_ID)
+            .set("x", x)
+            .set("y", y)
+            .set("width", width)
+            .set("height", height)
+            .set("stroke", "none");
+        let gradient_fill = format!("url(#{})", GRADIENT_ID);
+        let doc = match &self.background {
+            Background::Transparent => doc,
+            Background::Solid(colour) => doc.add(rect().set("fill", colour.as_str())),
+            Background::LinearGradient{angle, stops} => {
+                let (sin, cos) = angle.sin_cos();
+                let gradient = add_stops(LinearGradient::new(), stops)
+                    .set("id", GRADIENT_ID)
+                    .set("x1", 0.5 - sin/2.0)
+                    .set("y1", 0.5 - cos/2.0)
+                    .set("x2", 0.5 + sin/2.0)
+                    .set("y2", 0.5 + cos/2.0);
+                doc.add(Definitions::new().add(gradient)).add(rect().set("fill", gradient_fill))
+            }
+            Background::RadialGradient(stops) => {
+                //r is a fraction of the bounding box, this reaches the corners
+                let gradient = add_stops(RadialGradient::new(), stops)
+                    .set("id", GRADIENT_ID)
+                    .set("r", std::f32::consts::FRAC_1_SQRT_2);
+                doc.add(Definitions::new().add(gradient)).add(rect().set("fill", gradient_fill))
+            }
+            Background::Image(href) => {
+                let image = Image::new()
+                    .set("id", BACKGROUND_ID)
+                    .set("href", href.as_str())
+                    .set("x", x)
+                    .set("y", y)
+                    .set("width", width)
+                    .set("height", height)
+                    .set("preserveAspectRatio", "xMidYMid slice");
+                doc.add(image)
+            }
+        };
+        (doc, self.centre())
+    }
+}
+
+//children can only be read back as text, so the id is parsed out of that
+pub(crate) fn is_background(node:&dyn Node) -> Result<bool, Error> {
+    let text = node.to_string();
+    Ok(match svg::read(&text)?.next() {
+        Some(svg::parser::Event::Tag(_, _, attrs)) => attrs.get("id").is_some_and(|id| id.to_string() == BACKGROUND_ID),
+        _ => false,
+    })
+}
+
+//a copy of the document with the background taken out
+pub fn without_background(doc:&Document) -> Result<Document, Error> {
+    let mut doc = doc.clone();
+    let mut keep = Vec::new();
+    for child in doc.get_children() {
+        keep.push(!is_background(child.as_ref())?);
+    }
+    let mut keep = keep.into_iter();
+    doc.get_children_mut().retain(|_| keep.next().unwrap_or(true));
+    Ok(doc)
+}
+
+fn add_stops<T:Node>(mut gradient:T, stops:&[(f32,String)]) -> T {
+    for (offset, colour) in stops {
+        gradient.append(Stop::new().set("offset", *offset).set("stop-color", colour.as_str()));
+    }
+    gradient
+}
@@ -0,0 +1,333 @@
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use svg::Document;
+
+use gallifreyan as Gal;
+use Gal::canvas::{Background, Canvas};
+use Gal::cutter::{Outline, StencilOptions};
+use Gal::ctx::{ColourContext, Context, StrokeContext};
+use Gal::error::GallifreyanError;
+use Gal::layout::{Spacing, WordLayout};
+use Gal::plotter::{MachineOptions, Plot};
+use Gal::pord::PordOrCord;
+use Gal::raster::{PngOptions, PngSize};
+use Gal::scene::Scene;
+use Gal::sentence::SentenceCircle;
+use Gal::stl::{Base, StlOptions};
+use Gal::translate::{self, GlyphKind};
+use Gal::word::Word;
+
+use crate::test;
+
+const USAGE: &str = "\
+usage:
+    gallifreyan render <text> [options]   draw a sentence
+    gallifreyan word <word> [options]     draw a single word circle
+    gallifreyan scene <file> [options]    draw a .toml or .json scene description
+    gallifreyan alphabet                  list the letters and how they're drawn
+    gallifreyan demo [name]               run the test drawing into Imgs/
+
+options:
+    -o, --output <path>      where to save, defaults to out.svg
+    --format <svg|png|gcode|hpgl|dxf|stl>
+                             defaults to the output's extension, gcode & hpgl are always plotted
+    --size <n>               canvas size in pixels, default 2048
+    --stroke <n>             line thickness, default 20
+    --bg <colour>            background colour or 'transparent', default white
+    --margin <n>             empty space around the drawing, default 0
+    --fit                    fit the viewBox to the drawing, --margin around it
+    --spacing <even|weighted>
+    --plot                   single centre line strokes in pen order, for pen plotters
+    --bed <w>x<h>            gcode, hpgl, dxf & stl machine area in mm, default 297x210
+    --tolerance <mm>         how closely gcode, hpgl, dxf & stl lines follow curves, default 0.1
+    --feed <mm/min>          gcode & hpgl drawing speed, default 1500
+    --stencil                bridge islands in dxf outlines so they don't fall out when cut
+    --bridge <mm>            stencil bridge width, default 1.5
+    --bridges <n>            stencil bridges per island, default 2
+    --height <mm>            how far stl shapes stand up, default 2
+    --base <mm>              stand them on a plate this thick, the size of the canvas
+    --round                  make the plate a disc
+    --ascii                  write ascii stl instead of binary";
+const DEFAULT_SIZE :u64 = 2048;
+const DEFAULT_STROKE :f32 = 20.0;
+const SENTENCE_RATIO :f32 = 0.47;
+const WORD_RATIO :f32 = 0.35;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {Svg, Png, Gcode, Hpgl, Dxf, Stl}
+#[derive(Debug, Clone)]
+struct RenderArgs {
+    text:String,
+    output:Option<PathBuf>,
+    format:Option<Format>,
+    size:u64,
+    stroke:f32,
+    bg:String,
+    margin:f32,
+    fit:bool,
+    spacing:Spacing,
+    plot:bool,
+    machine:MachineOptions,
+    stencil:Option<StencilOptions>,
+    stl:StlOptions,
+    round:bool,
+    //every option that was passed, for commands that don't take them all
+    given:Vec<String>,
+}
+
+pub fn run(mut args:impl Iterator<Item = String>) -> Result<(), Error> {
+    match args.next().as_deref() {
+        Some("render") => {
+            let opts = RenderArgs::parse(args)?;
+            if translate::translate(&opts.text).is_empty() {
+                return Err(bad_input("nothing to render"))
+            }
+            let doc = draw_sentence(&opts).map_err(Error::other)?;
+            save(&opts, &doc)
+        }
+        Some("word") => {
+            let opts = RenderArgs::parse(args)?;
+            if translate::translate_word(&opts.text).is_empty() {
+                return Err(bad_input("nothing to render"))
+            }
+            let doc = draw_word(&opts).map_err(Error::other)?;
+            save(&opts, &doc)
+        }
+        Some("scene") => {
+            let mut opts = RenderArgs::parse(args)?;
+            //the scene file sets these itself
+            if let Some(flag) = ["--size", "--stroke", "--bg", "--spacing"].into_iter().find(|flag| opts.given.iter().any(|given| given == flag)) {
+                return Err(bad_input(format!("{} doesn't apply to scenes, set it in the scene file instead", flag)))
+            }
+            let mut scene = Scene::load(Path::new(&opts.text)).map_err(Error::other)?;
+            scene.canvas.plot |= opts.plotting();
+            opts.stroke = scene.pen_width();
+            let mut doc = scene.build().map_err(Error::other)?;
+            if opts.fit {
+                doc = Gal::bounds::fit_view_box(doc, opts.margin)?;
+            }
+            save(&opts, &doc)
+        }
+        Some("alphabet") => {
+            print_alphabet();
+            Ok(())
+        }
+        Some("demo") => {
+            let name = args.next().unwrap_or("test".to_string());
+            test::test(&name).map(|_| ())
+        }
+        Some("help") | Some("-h") | Some("--help") | None => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(bad_input(format!("unknown command '{}'", other))),
+    }
+}
+
+impl RenderArgs {
+    fn parse(mut args:impl Iterator<Item = String>) -> Result<RenderArgs, Error> {
+        let mut text = Vec::new();
+        let mut opts = RenderArgs {
+            text: String::new(),
+            output: None,
+            format: None,
+            size: DEFAULT_SIZE,
+            stroke: DEFAULT_STROKE,
+            bg: "white".to_string(),
+            margin: 0.0,
+            fit: false,
+            spacing: Spacing::Even,
+            plot: false,
+            machine: MachineOptions::default(),
+            stencil: None,
+            stl: StlOptions::default(),
+            round: false,
+            given: Vec::new(),
+        };
+        while let Some(arg) = args.next() {
+            if arg.starts_with('-') {
+                opts.given.push(arg.clone());
+            }
+            let mut value = || args.next().ok_or_else(|| bad_input(format!("{} needs a value", arg)));
+            match arg.as_str() {
+                "-o" | "--output" => opts.output = Some(PathBuf::from(value()?)),
+                "--format" => opts.format = Some(match value()?.as_str() {
+                    "svg" => Format::Svg,
+                    "png" => Format::Png,
+                    "gcode" => Format::Gcode,
+                    "hpgl" => Format::Hpgl,
+                    "dxf" => Format::Dxf,
+                    "stl" => Format::Stl,
+                    other => return Err(bad_input(format!("unknown format '{}'", other))),
+                }),
+                "--size" => opts.size = value()?.parse().map_err(bad_input)?,
+                "--stroke" => opts.stroke = value()?.parse().map_err(bad_input)?,
+                "--bg" => opts.bg = value()?,
+                "--margin" => opts.margin = value()?.parse().map_err(bad_input)?,
+                "--fit" => opts.fit = true,
+                "--plot" => opts.plot = true,
+                "--bed" => {
+                    let bed = value()?;
+                    let (width, height) = bed.split_once('x').ok_or_else(|| bad_input(format!("bed size '{}' should be <w>x<h>", bed)))?;
+                    opts.machine.set_bed(width.parse().map_err(bad_input)?, height.parse().map_err(bad_input)?);
+                }
+                "--tolerance" => opts.machine.set_tolerance(value()?.parse().map_err(bad_input)?).map_err(bad_input)?,
+                "--feed" => opts.machine.set_feed_rate(value()?.parse().map_err(bad_input)?),
+                "--stencil" => _ = opts.stencil.get_or_insert_with(StencilOptions::default),
+                "--bridge" => opts.stencil.get_or_insert_with(StencilOptions::default).set_width(value()?.parse().map_err(bad_input)?).map_err(bad_input)?,
+                "--bridges" => opts.stencil.get_or_insert_with(StencilOptions::default).set_count(value()?.parse().map_err(bad_input)?),
+                "--height" => opts.stl.set_height(value()?.parse().map_err(bad_input)?),
+                "--base" => opts.stl.set_base(Base::Rectangle(value()?.parse().map_err(bad_input)?)),
+                "--round" => opts.round = true,
+                "--ascii" => opts.stl.set_binary(false),
+                "--spacing" => opts.spacing = match value()?.as_str() {
+                    "even" => Spacing::Even,
+                    "weighted" => Spacing::Weighted,
+                    other => return Err(bad_input(format!("unknown spacing '{}'", other))),
+                },
+                _ if arg.starts_with('-') => return Err(bad_input(format!("unknown option '{}'", arg))),
+                _ => text.push(arg),
+            }
+        }
+        if text.is_empty() {
+            return Err(bad_input("nothing to draw"))
+        }
+        opts.text = text.join(" ");
+        if let (Some(format), Some(ext_format)) = (opts.format, opts.ext_format())
+            && format != ext_format {
+            return Err(bad_input(format!("--format {} doesn't match the output file {}", format!("{:?}", format).to_lowercase(), opts.output().display())))
+        }
+        Ok(opts)
+    }
+    fn format(&self) -> Format {
+        self.format.or(self.ext_format()).unwrap_or(Format::Svg)
+    }
+    //what the output's extension says it should be, if it's one we know
+    fn ext_format(&self) -> Option<Format> {
+        self.output.as_ref()
+            .and_then(|path| path.extension())
+            .and_then(|ext| match ext.to_ascii_lowercase().to_str()? {
+                "svg" => Some(Format::Svg),
+                "png" => Some(Format::Png),
+                "gcode" | "nc" | "gc" => Some(Format::Gcode),
+                "hpgl" | "plt" => Some(Format::Hpgl),
+                "dxf" => Some(Format::Dxf),
+                "stl" => Some(Format::Stl),
+                _ => None,
+            })
+    }
+    fn output(&self) -> PathBuf {
+        let ext = match self.format() {
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Gcode => "gcode",
+            Format::Hpgl => "hpgl",
+            Format::Dxf => "dxf",
+            Format::Stl => "stl",
+        };
+        match &self.output {
+            Some(path) if path.extension().is_some() => path.clone(),
+            Some(path) => path.with_extension(ext),
+            None => PathBuf::from("out").with_extension(ext),
+        }
+    }
+    fn transparent(&self) -> bool {
+        self.bg == "transparent"
+    }
+    //machines can only follow single strokes
+    fn plotting(&self) -> bool {
+        self.plot || matches!(self.format(), Format::Gcode | Format::Hpgl)
+    }
+    fn finish(&self, mut doc:Document) -> Result<Document, GallifreyanError> {
+        if self.plotting() {
+            let plot = Plot::new(&doc, self.stroke)?;
+            let (before, after) = plot.travel();
+            println!("pen up travel {:.0} down to {:.0}", before, after);
+            doc = plot.document();
+        }
+        if self.fit {
+            doc = Gal::bounds::fit_view_box(doc, self.margin)?;
+        }
+        Ok(doc)
+    }
+    fn canvas(&self) -> (Document, Context) {
+        let (bg, background) = if self.transparent() {
+            ("none", Background::Transparent)
+        } else {(self.bg.as_str(), Background::solid(&self.bg))};
+        let mut canvas = Canvas::new(self.size as f32, self.size as f32);
+        canvas.set_margin(self.margin);
+        canvas.set_background(background);
+        canvas.set_title(&self.text);
+        canvas.set_desc(&format!("\"{}\" in Sherman's Circular Gallifreyan, written {}", self.text, translate::transliterate_text(&self.text)));
+        let (doc, svg_origin) = canvas.build();
+        let origin = PordOrCord::gal_origin(svg_origin);
+        let colour = ColourContext::new(bg, "none", "black");
+        let mut ctx = Context::new(colour, StrokeContext::new(self.stroke), origin);
+        ctx.set_centre_lines(self.plotting());
+        (doc, ctx)
+    }
+}
+
+fn draw_sentence(opts:&RenderArgs) -> Result<Document, GallifreyanError> {
+    let (doc, ctx) = opts.canvas();
+    let radius = opts.size as f32 * SENTENCE_RATIO;
+    let layout = WordLayout::new(opts.spacing);
+    let sentence = SentenceCircle::from_text(&opts.text, PordOrCord::gal_origin(ctx.origin()), radius, &layout, ctx)?;
+    opts.finish(sentence.try_draw(doc)?)
+}
+
+fn draw_word(opts:&RenderArgs) -> Result<Document, GallifreyanError> {
+    let (doc, ctx) = opts.canvas();
+    let radius = opts.size as f32 * WORD_RATIO;
+    let layout = WordLayout::new(opts.spacing);
+    let word = layout.word_circle_from_text(&opts.text, PordOrCord::gal_origin(ctx.origin()), radius, ctx)?;
+    opts.finish(word.try_draw(doc)?)
+}
+
+fn save(opts:&RenderArgs, doc:&Document) -> Result<(), Error> {
+    let path = opts.output();
+    println!("Saving under {}", path.display());
+    match opts.format() {
+        Format::Svg => svg::save(path, doc),
+        Format::Png => {
+            let mut png = PngOptions::new(PngSize::Scale(1.0));
+            png.set_transparent(opts.transparent());
+            std::fs::write(path, Gal::raster::render_png_bytes(doc, &png)?)
+        }
+        Format::Gcode => std::fs::write(path, Gal::gcode::gcode(&Plot::new(doc, opts.stroke)?, &opts.machine)),
+        Format::Hpgl => std::fs::write(path, Gal::hpgl::hpgl(&Plot::new(doc, opts.stroke)?, &opts.machine)),
+        Format::Dxf => {
+            let mut outline = Outline::new(doc, &opts.machine)?;
+            if let Some(stencil) = &opts.stencil {
+                let report = outline.stencil(stencil);
+                println!("{} bridges holding {} islands", report.bridges(), report.bridged());
+                if report.unbridged() > 0 {
+                    return Err(Error::other(format!("{} islands couldn't be bridged and would fall out, try a narrower --bridge", report.unbridged())))
+                }
+            }
+            std::fs::write(path, Gal::dxf::dxf(&outline))
+        }
+        Format::Stl => {
+            let mut stl = opts.stl;
+            if let (true, Base::Rectangle(thickness)) = (opts.round, stl.base()) {
+                stl.set_base(Base::Disc(thickness));
+            }
+            std::fs::write(path, Gal::stl::stl(&Outline::new(doc, &opts.machine)?, &stl)?)
+        }
+    }
+}
+
+fn print_alphabet() {
+    for glyph in translate::alphabet() {
+        let description = match glyph.kind() {
+            GlyphKind::Consonant(stem_type, decoration) => format!("{:?} stem, {:?}", stem_type, decoration),
+            GlyphKind::Vowel(kind) => format!("{:?} vowel", kind),
+        };
+        println!("{:<4}{}", glyph.text(), description);
+    }
+}
+
+fn bad_input(msg:impl ToString) -> Error {
+    Error::new(ErrorKind::InvalidInput, format!("{}\n\n{}", msg.to_string(), USAGE))
+}
@@ -1,12 +1,15 @@
 use std::rc::Rc;
 
+use crate::error::GallifreyanError;
 use crate::pord::PordOrCord;
 
 #[derive(Debug, Clone, Default)]
 pub struct Context {
     colour: ColourContext,
     stroke: StrokeContext,
-    origin: Rc<PordOrCord>
+    origin: Rc<PordOrCord>,
+    //stroke down the middle of word lines instead of filling between their edges, for pen plotters
+    centre_lines: bool,
 }
 #[derive(Debug, Clone, Hash)]
 pub struct ColourContext {
@@ -83,7 +86,7 @@ impl StrokeContext {
 
 impl Context {
     pub fn new(colour:ColourContext, stroke:StrokeContext, origin:Rc<PordOrCord>) -> Context {
-        Context { colour, stroke, origin:origin.clone() }
+        Context { colour, stroke, origin:origin.clone(), centre_lines: false }
     }
     pub fn colour(&self) -> &ColourContext {
         &self.colour
@@ -92,15 +95,32 @@ impl Context {
         &self.stroke
     }
     pub fn origin(&self) -> (f32,f32) {
+        match self.try_origin() {
+            Ok(xy) => xy,
+            Err(_) => panic!("Anchor is a not a Cord")
+        }
+    }
+    pub fn try_origin(&self) -> Result<(f32,f32), GallifreyanError> {
         match *self.origin {
-            PordOrCord::Cord(x,y) => (x,y),
-            _ => panic!("Anchor is a not a Cord")
+            PordOrCord::Cord(x,y) => Ok((x,y)),
+            _ => Err(GallifreyanError::InvalidAnchor)
         }
     }
     pub fn new_strokewidth(&self, strokewidth:f32) -> Context {
-        Context { colour: self.colour.clone(), stroke: StrokeContext::new(strokewidth) , origin:self.origin.clone() }
+        Context { colour: self.colour.clone(), stroke: StrokeContext::new(strokewidth) , origin:self.origin.clone(), centre_lines: self.centre_lines }
+    }
+    //strokeless, filled with the stroke colour, for dots and the like
+    pub fn new_filled(&self) -> Context {
+        let colour = ColourContext::new(self.colour.bg(), self.colour.stroke(), "none");
+        Context { colour, stroke: StrokeContext::new(0.0), origin:self.origin.clone(), centre_lines: self.centre_lines }
     }
     pub fn set_origin(&mut self, svg_origin: Rc<PordOrCord>) {
         self.origin = svg_origin;
     }
+    pub fn centre_lines(&self) -> bool {
+        self.centre_lines
+    }
+    pub fn set_centre_lines(&mut self, centre_lines:bool) {
+        self.centre_lines = centre_lines;
+    }
 }
\ No newline at end of file
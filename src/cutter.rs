@@ -0,0 +1,541 @@
+use std::collections::VecDeque;
+use std::io::Error;
+
+use svg::Document;
+use svg::node::Attributes;
+use svg::node::element::path::Data;
+use svg::node::element::tag::Type;
+use svg::parser::Event;
+
+use crate::BACKGROUND_ID;
+use crate::geometry::dist;
+use crate::plotter::{self, JOIN_TOLERANCE, MachineOptions};
+use crate::utils::{self, SvgPosition};
+
+//bridge width in mm, & how many hold each island
+const DEFAULT_BRIDGE_WIDTH :f32 = 1.5;
+const DEFAULT_BRIDGES :usize = 2;
+//rounds of bridging before giving up on islands that only reach each other
+const MAX_ROUNDS :usize = 8;
+//the island search looks no finer than this many cells across
+const MAX_CELLS :f32 = 1000.0;
+//directions tried out of an island, from up to so many cells round its edge
+const DIRECTIONS :usize = 72;
+const MAX_STARTS :usize = 256;
+
+//a filled shape, evenodd between its contours
+#[derive(Debug, Clone)]
+struct Ink {
+    contours:Vec<Vec<SvgPosition>>,
+    min:SvgPosition,
+    max:SvgPosition,
+}
+//a strip of sheet left across the ink, from start to end
+#[derive(Debug, Clone, Copy)]
+struct Bridge {
+    start:SvgPosition,
+    end:SvgPosition,
+    half_width:f32,
+}
+//the drawing cut into cells, each inked or sheet, for finding islands
+#[derive(Debug, Clone)]
+struct Grid {
+    origin:SvgPosition,
+    cell:f32,
+    cols:usize,
+    rows:usize,
+    inked:Vec<bool>,
+    //which piece of sheet each cell belongs to, & whether that piece reaches the edge
+    labels:Vec<Option<usize>>,
+    pieces:Vec<(usize, bool)>,
+}
+//the edges of everything drawn, to cut out of a sheet on a laser or cnc.
+//the whole drawing is kept as filled shapes, so stroked circles & lines get an edge either side
+#[derive(Debug, Clone)]
+pub struct Outline {
+    view_box:(f32,f32,f32,f32),
+    machine:MachineOptions,
+    title:Option<String>,
+    inks:Vec<Ink>,
+    bridges:Vec<Bridge>,
+}
+//cutting the ink away leaves islands of sheet inside letters & words, these get bridged back on. sizes in mm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StencilOptions {
+    width:f32,
+    count:usize,
+}
+//what Outline::stencil managed. unbridged islands will fall out when cut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StencilReport {
+    bridges:usize,
+    bridged:usize,
+    unbridged:usize,
+}
+
+impl Ink {
+    fn new(contours:Vec<Vec<SvgPosition>>) -> Option<Ink> {
+        let contours: Vec<Vec<SvgPosition>> = contours.into_iter().filter(|contour| contour.len() > 2).collect();
+        let points = contours.iter().flatten();
+        let min = points.clone().fold(SvgPosition(f32::MAX, f32::MAX), |min, p| SvgPosition(min.0.min(p.0), min.1.min(p.1)));
+        let max = points.fold(SvgPosition(f32::MIN, f32::MIN), |max, p| SvgPosition(max.0.max(p.0), max.1.max(p.1)));
+        (!contours.is_empty()).then_some(Ink { contours, min, max })
+    }
+    fn contains(&self, p:SvgPosition) -> bool {
+        if p.0 < self.min.0 || p.1 < self.min.1 || p.0 > self.max.0 || p.1 > self.max.1 {
+            return false
+        }
+        let mut inside = false;
+        for (a, b) in self.contours.iter().flat_map(|contour| edges(contour)) {
+            if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1)*(b.0 - a.0)/(b.1 - a.1) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+impl Bridge {
+    //reaching `extend` past both ends, so it's well into the sheet
+    fn new(start:SvgPosition, end:SvgPosition, half_width:f32, extend:f32) -> Bridge {
+        let (ux, uy) = unit(start, end);
+        Bridge {
+            start: SvgPosition(start.0 - ux*extend, start.1 - uy*extend),
+            end: SvgPosition(end.0 + ux*extend, end.1 + uy*extend),
+            half_width,
+        }
+    }
+    fn contains(&self, p:SvgPosition) -> bool {
+        let (ux, uy) = unit(self.start, self.end);
+        let (vx, vy) = (p.0 - self.start.0, p.1 - self.start.1);
+        let along = vx*ux + vy*uy;
+        along >= 0.0 && along <= dist(self.start, self.end) && (vx*uy - vy*ux).abs() <= self.half_width
+    }
+    fn corners(&self) -> Vec<SvgPosition> {
+        let (ux, uy) = unit(self.start, self.end);
+        let (nx, ny) = (-uy*self.half_width, ux*self.half_width);
+        vec![
+            SvgPosition(self.start.0 + nx, self.start.1 + ny),
+            SvgPosition(self.end.0 + nx, self.end.1 + ny),
+            SvgPosition(self.end.0 - nx, self.end.1 - ny),
+            SvgPosition(self.start.0 - nx, self.start.1 - ny),
+        ]
+    }
+}
+
+impl Grid {
+    fn new(outline:&Outline, cell:f32) -> Grid {
+        let (x, y, width, height) = outline.view_box;
+        let (cols, rows) = ((width/cell).ceil() as usize, (height/cell).ceil() as usize);
+        let mut grid = Grid { origin: SvgPosition(x, y), cell, cols, rows, inked: Vec::new(), labels: Vec::new(), pieces: Vec::new() };
+        grid.inked = (0..cols*rows).map(|i| outline.inked(grid.centre(i))).collect();
+        grid.label();
+        grid
+    }
+    fn centre(&self, i:usize) -> SvgPosition {
+        let (col, row) = (i % self.cols, i / self.cols);
+        SvgPosition(self.origin.0 + (col as f32 + 0.5)*self.cell, self.origin.1 + (row as f32 + 0.5)*self.cell)
+    }
+    fn at(&self, p:SvgPosition) -> Option<usize> {
+        let (col, row) = ((p.0 - self.origin.0)/self.cell, (p.1 - self.origin.1)/self.cell);
+        if col < 0.0 || row < 0.0 || col >= self.cols as f32 || row >= self.rows as f32 {
+            return None
+        }
+        Some(row as usize*self.cols + col as usize)
+    }
+    //up, down, left & right, those that are on the grid
+    fn neighbours(&self, i:usize) -> impl Iterator<Item = usize> {
+        let (col, row, cols, rows) = (i % self.cols, i / self.cols, self.cols, self.rows);
+        [
+            (row > 0).then(|| i - cols),
+            (row + 1 < rows).then(|| i + cols),
+            (col > 0).then(|| i - 1),
+            (col + 1 < cols).then(|| i + 1),
+        ].into_iter().flatten()
+    }
+    fn on_border(&self, i:usize) -> bool {
+        let (col, row) = (i % self.cols, i / self.cols);
+        col == 0 || row == 0 || col + 1 == self.cols || row + 1 == self.rows
+    }
+    //floods each piece of sheet
+    fn label(&mut self) {
+        self.labels = vec![None; self.inked.len()];
+        self.pieces.clear();
+        for first in 0..self.inked.len() {
+            if self.inked[first] || self.labels[first].is_some() {
+                continue
+            }
+            let piece = self.pieces.len();
+            let (mut size, mut edge) = (0, false);
+            let mut queue = VecDeque::from([first]);
+            self.labels[first] = Some(piece);
+            while let Some(i) = queue.pop_front() {
+                size += 1;
+                edge |= self.on_border(i);
+                for next in self.neighbours(i).collect::<Vec<usize>>() {
+                    if !self.inked[next] && self.labels[next].is_none() {
+                        self.labels[next] = Some(piece);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            self.pieces.push((size, edge));
+        }
+    }
+    //pieces that don't reach the edge of the sheet, leaving out slivers smaller than `min_area`
+    fn islands(&self, min_area:f32) -> Vec<usize> {
+        self.pieces.iter().enumerate()
+            .filter(|(_, (size, edge))| !edge && *size as f32*self.cell.powi(2) >= min_area)
+            .map(|(piece, _)| piece)
+            .collect()
+    }
+    fn clear(&mut self, bridge:&Bridge) {
+        for i in 0..self.inked.len() {
+            if self.inked[i] && bridge.contains(self.centre(i)) {
+                self.inked[i] = false;
+            }
+        }
+    }
+    //the shortest ways across the ink to other sheet, as spread out round the island as they can be
+    fn bridges(&self, island:usize, count:usize, half_width:f32) -> Vec<Bridge> {
+        let edge: Vec<usize> = (0..self.inked.len())
+            .filter(|i| self.labels[*i] == Some(island) && self.neighbours(*i).any(|next| self.inked[next]))
+            .collect();
+        let mut options = Vec::new();
+        for i in edge.iter().step_by((edge.len()/MAX_STARTS).max(1)) {
+            let start = self.centre(*i);
+            for angle in utils::ang_iter(DIRECTIONS) {
+                if let Some(end) = self.exit(start, angle, island) {
+                    options.push((start, end, dist(start, end)));
+                }
+            }
+        }
+        options.sort_by(|a, b| a.2.total_cmp(&b.2));
+        let Some(&(_, _, shortest)) = options.first() else {
+            return Vec::new()
+        };
+        //anything about as short will do
+        options.retain(|option| option.2 <= shortest*1.5 + 2.0*self.cell);
+        let mut chosen = vec![options[0]];
+        while chosen.len() < count {
+            let spread = |option:&(SvgPosition, SvgPosition, f32)| chosen.iter().map(|other| dist(option.0, other.0)).fold(f32::MAX, f32::min);
+            let furthest = *options.iter().max_by(|a, b| spread(a).total_cmp(&spread(b))).expect("options isn't empty");
+            if spread(&furthest) < 4.0*half_width {
+                break
+            }
+            chosen.push(furthest);
+        }
+        chosen.into_iter().map(|(start, end, _)| Bridge::new(start, end, half_width, self.cell)).collect()
+    }
+    //heads off in a straight line, through ink, to where it comes out on some other sheet
+    fn exit(&self, start:SvgPosition, angle:f32, island:usize) -> Option<SvgPosition> {
+        let (sin, cos) = angle.sin_cos();
+        let step = self.cell/2.0;
+        let furthest = (self.cols + self.rows) as f32*self.cell;
+        let mut inked = false;
+        let mut along = 0.0;
+        while along < furthest {
+            along += step;
+            let p = SvgPosition(start.0 + along*sin, start.1 + along*cos);
+            //off the grid counts as sheet
+            let Some(i) = self.at(p) else {
+                return inked.then_some(p)
+            };
+            if self.inked[i] {
+                inked = true;
+            } else if inked || along > 2.0*self.cell || self.labels[i] != Some(island) {
+                return (inked && self.labels[i] != Some(island)).then_some(p)
+            }
+        }
+        None
+    }
+}
+
+impl Default for StencilOptions {
+    fn default() -> Self {
+        Self { width: DEFAULT_BRIDGE_WIDTH, count: DEFAULT_BRIDGES }
+    }
+}
+
+impl StencilOptions {
+    pub fn new(width:f32) -> StencilOptions {
+        StencilOptions { width, ..Default::default() }
+    }
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    //has to be above 0, a bridge that thin never clears anything
+    pub fn set_width(&mut self, width:f32) -> Result<(), Error> {
+        if width.is_nan() || width <= 0.0 {
+            return Err(Error::other(format!("bridge width has to be more than 0, not {}", width)))
+        }
+        self.width = width;
+        Ok(())
+    }
+    //bridges for each island, fewer if it's too small to spread them out
+    pub fn set_count(&mut self, count:usize) {
+        self.count = count.max(1);
+    }
+}
+
+impl StencilReport {
+    pub fn bridges(&self) -> usize {
+        self.bridges
+    }
+    pub fn bridged(&self) -> usize {
+        self.bridged
+    }
+    pub fn unbridged(&self) -> usize {
+        self.unbridged
+    }
+}
+
+impl Outline {
+    //draw the document as normal, not with centre lines. the bed & tolerance come from the machine,
+    //anything filled with the background colour counts as unfilled
+    pub fn new(doc:&Document, machine:&MachineOptions) -> Result<Outline, Error> {
+        let content = doc.to_string();
+        let mut view_box = None;
+        let mut title = None;
+        let mut in_title = false;
+        let mut elements: Vec<(String, Attributes)> = Vec::new();
+        for event in svg::read(&content)? {
+            match event {
+                Event::Error(err) => return Err(Error::other(err)),
+                Event::Tag("svg", Type::Start, attrs) => {
+                    view_box = attrs.get("viewBox").and_then(|value| plotter::parse_view_box(value));
+                }
+                Event::Tag("title", Type::Start, _) => in_title = true,
+                Event::Text(text) if in_title => {
+                    title = Some(text.to_string());
+                    in_title = false;
+                }
+                Event::Tag(tag @ ("circle" | "line" | "path" | "rect"), _, attrs) => elements.push((tag.to_string(), attrs)),
+                _ => (),
+            }
+        }
+        let view_box = view_box.ok_or_else(|| Error::other("the svg needs a viewBox to cut"))?;
+        let tolerance = machine.tolerance()/machine.scale(view_box);
+        let background = elements.iter()
+            .find(|(_, attrs)| attrs.get("id").map(|id| id.to_string()).as_deref() == Some(BACKGROUND_ID))
+            .and_then(|(_, attrs)| attrs.get("fill").map(|fill| fill.to_string()));
+        let mut inks = Vec::new();
+        for (tag, attrs) in &elements {
+            let get = |name:&str| attrs.get(name).map(|value| value.to_string());
+            let num = |name:&str| get(name).and_then(|value| value.parse::<f32>().ok()).unwrap_or(0.0);
+            let paint = |name:&str| get(name).filter(|colour| colour != "none" && Some(colour) != background.as_ref());
+            let half = num("stroke-width")/2.0;
+            let (fill, stroke) = (paint("fill").is_some(), paint("stroke").is_some() && half > 0.0);
+            let contours = match tag.as_str() {
+                "circle" => {
+                    let (centre, radius) = (SvgPosition(num("cx"), num("cy")), num("r"));
+                    match (fill, stroke) {
+                        (false, true) if radius > half => vec![circle(centre, radius + half, tolerance), circle(centre, radius - half, tolerance)],
+                        (_, true) => vec![circle(centre, radius + half, tolerance)],
+                        (true, false) => vec![circle(centre, radius, tolerance)],
+                        (false, false) => continue,
+                    }
+                }
+                "line" if stroke => stroke_outline(&[SvgPosition(num("x1"), num("y1")), SvgPosition(num("x2"), num("y2"))], half),
+                "path" if fill || stroke => {
+                    let data = Data::parse(&get("d").unwrap_or_default()).map_err(Error::other)?;
+                    let lines: Vec<Vec<SvgPosition>> = plotter::sub_paths(&data).iter().flat_map(|path| path.flatten(tolerance)).collect();
+                    if fill {
+                        lines
+                    } else {
+                        lines.iter().flat_map(|line| stroke_outline(line, half)).collect()
+                    }
+                }
+                _ => continue,
+            };
+            inks.extend(Ink::new(contours));
+        }
+        Ok(Outline { view_box, machine: *machine, title, inks, bridges: Vec::new() })
+    }
+    pub fn view_box(&self) -> (f32,f32,f32,f32) {
+        self.view_box
+    }
+    pub fn machine(&self) -> &MachineOptions {
+        &self.machine
+    }
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+    //every shape's contours in mm with y going up, evenodd inside each shape. bridges aren't included
+    pub fn shapes(&self) -> Vec<Vec<Vec<(f32,f32)>>> {
+        self.inks.iter()
+            .map(|ink| ink.contours.iter()
+                .map(|contour| contour.iter().map(|point| self.machine.to_bed(self.view_box, *point)).collect())
+                .collect())
+            .collect()
+    }
+    //leaves strips of sheet across the ink, so nothing inside falls out once it's cut
+    pub fn stencil(&mut self, options:&StencilOptions) -> StencilReport {
+        let (_, _, width, height) = self.view_box;
+        let bridge = options.width/self.machine.scale(self.view_box);
+        let mut grid = Grid::new(self, (bridge/3.0).max(width.max(height)/MAX_CELLS));
+        let mut islands = None;
+        let before = self.bridges.len();
+        for round in 0..MAX_ROUNDS {
+            let found = grid.islands(bridge.powi(2));
+            islands.get_or_insert(found.len());
+            let count = if round == 0 {options.count} else {1};
+            let bridges: Vec<Bridge> = found.into_iter().flat_map(|island| grid.bridges(island, count, bridge/2.0)).collect();
+            if bridges.is_empty() {
+                break
+            }
+            for bridge in &bridges {
+                grid.clear(bridge);
+            }
+            grid.label();
+            self.bridges.extend(bridges);
+        }
+        let left = grid.islands(bridge.powi(2)).len();
+        StencilReport {
+            bridges: self.bridges.len() - before,
+            bridged: islands.unwrap_or(0) - left,
+            unbridged: left,
+        }
+    }
+    //every edge between ink & sheet, in mm with y going up. ink that overlaps only gets cut round the outside
+    pub fn cut_lines(&self) -> Vec<Vec<(f32,f32)>> {
+        let offset = 2.0*self.machine.tolerance()/self.machine.scale(self.view_box);
+        let mut sources: Vec<Vec<SvgPosition>> = self.inks.iter().flat_map(|ink| ink.contours.iter().cloned()).collect();
+        sources.extend(self.bridges.iter().map(Bridge::corners));
+        let bounds: Vec<(SvgPosition,SvgPosition)> = sources.iter().map(|source| bounds(source)).collect();
+        let mut result = Vec::new();
+        for (num, source) in sources.iter().enumerate() {
+            let mut lines: Vec<Vec<SvgPosition>> = Vec::new();
+            for (a, b) in edges(source) {
+                if dist(a, b) == 0.0 {
+                    continue
+                }
+                let mut splits = vec![0.0, 1.0];
+                for (other, points) in sources.iter().enumerate() {
+                    if other == num || !overlaps(bounds[other], bounds_of(a, b)) {
+                        continue
+                    }
+                    splits.extend(edges(points).filter_map(|(c, d)| crossing(a, b, c, d)));
+                }
+                splits.sort_by(f32::total_cmp);
+                for pair in splits.windows(2) {
+                    let (p, q) = (lerp(a, b, pair[0]), lerp(a, b, pair[1]));
+                    if dist(p, q) < JOIN_TOLERANCE || !self.on_edge(p, q, offset) {
+                        continue
+                    }
+                    match lines.last_mut() {
+                        Some(line) if dist(*line.last().expect("lines aren't empty"), p) < JOIN_TOLERANCE => line.push(q),
+                        _ => lines.push(vec![p, q]),
+                    }
+                }
+            }
+            //contours are loops, so the last line might carry on into the first
+            if lines.len() > 1 && dist(lines[0][0], *lines[lines.len() - 1].last().expect("lines aren't empty")) < JOIN_TOLERANCE {
+                let mut last = lines.pop().expect("there's more than one");
+                last.extend(lines[0].drain(1..));
+                lines[0] = last;
+            }
+            result.extend(lines);
+        }
+        result.into_iter()
+            .map(|line| line.into_iter().map(|point| self.machine.to_bed(self.view_box, point)).collect())
+            .collect()
+    }
+    fn inked(&self, p:SvgPosition) -> bool {
+        self.inks.iter().any(|ink| ink.contains(p)) && !self.bridges.iter().any(|bridge| bridge.contains(p))
+    }
+    //ink one side & sheet the other
+    fn on_edge(&self, p:SvgPosition, q:SvgPosition, offset:f32) -> bool {
+        let (ux, uy) = unit(p, q);
+        let mid = SvgPosition((p.0 + q.0)/2.0, (p.1 + q.1)/2.0);
+        let left = SvgPosition(mid.0 - uy*offset, mid.1 + ux*offset);
+        let right = SvgPosition(mid.0 + uy*offset, mid.1 - ux*offset);
+        self.inked(left) != self.inked(right)
+    }
+}
+
+//each side of a closed loop of points, back round to the first
+fn edges(points:&[SvgPosition]) -> impl Iterator<Item = (SvgPosition, SvgPosition)> + '_ {
+    points.iter().copied().zip(points.iter().copied().cycle().skip(1))
+}
+
+fn unit(from:SvgPosition, to:SvgPosition) -> (f32,f32) {
+    let length = dist(from, to).max(f32::EPSILON);
+    ((to.0 - from.0)/length, (to.1 - from.1)/length)
+}
+
+fn lerp(a:SvgPosition, b:SvgPosition, t:f32) -> SvgPosition {
+    SvgPosition(a.0 + (b.0 - a.0)*t, a.1 + (b.1 - a.1)*t)
+}
+
+//how far along a to b it crosses c to d, not counting the ends of a to b
+fn crossing(a:SvgPosition, b:SvgPosition, c:SvgPosition, d:SvgPosition) -> Option<f32> {
+    let (rx, ry, sx, sy) = (b.0 - a.0, b.1 - a.1, d.0 - c.0, d.1 - c.1);
+    let denom = rx*sy - ry*sx;
+    if denom.abs() < f32::EPSILON {
+        return None
+    }
+    let (qx, qy) = (c.0 - a.0, c.1 - a.1);
+    let t = (qx*sy - qy*sx)/denom;
+    let u = (qx*ry - qy*rx)/denom;
+    (t > 0.0 && t < 1.0 && (0.0..=1.0).contains(&u)).then_some(t)
+}
+
+fn bounds(points:&[SvgPosition]) -> (SvgPosition,SvgPosition) {
+    points.iter().fold((SvgPosition(f32::MAX, f32::MAX), SvgPosition(f32::MIN, f32::MIN)), |(min, max), p| (
+        SvgPosition(min.0.min(p.0), min.1.min(p.1)),
+        SvgPosition(max.0.max(p.0), max.1.max(p.1)),
+    ))
+}
+
+fn bounds_of(a:SvgPosition, b:SvgPosition) -> (SvgPosition,SvgPosition) {
+    bounds(&[a, b])
+}
+
+fn overlaps(a:(SvgPosition,SvgPosition), b:(SvgPosition,SvgPosition)) -> bool {
+    a.0.0 <= b.1.0 && b.0.0 <= a.1.0 && a.0.1 <= b.1.1 && b.0.1 <= a.1.1
+}
+
+//points round a circle, close enough to be within the tolerance
+fn circle(centre:SvgPosition, radius:f32, tolerance:f32) -> Vec<SvgPosition> {
+    let step = 2.0*(1.0 - (tolerance/radius).min(1.0)).acos();
+    let num = ((2.0*std::f32::consts::PI/step.max(f32::EPSILON)).ceil() as usize).clamp(8, utils::MAX_ARC_POINTS);
+    utils::ang_iter(num).map(|angle| SvgPosition(centre.0 + radius*angle.cos(), centre.1 + radius*angle.sin())).collect()
+}
+
+//the edges of a line `half` either side of the points, closed lines give two loops
+fn stroke_outline(points:&[SvgPosition], half:f32) -> Vec<Vec<SvgPosition>> {
+    let closed = points.len() > 2 && dist(points[0], points[points.len() - 1]) < JOIN_TOLERANCE;
+    let points = if closed {&points[..points.len() - 1]} else {points};
+    let len = points.len();
+    if len < 2 {
+        return Vec::new()
+    }
+    //averaged between the sides either side of each point, longer round corners
+    let normal = |i:usize| {
+        let prev = if i > 0 {Some(points[i - 1])} else if closed {Some(points[len - 1])} else {None};
+        let next = if i + 1 < len {Some(points[i + 1])} else if closed {Some(points[0])} else {None};
+        let before = prev.map(|prev| unit(prev, points[i]));
+        let after = next.map(|next| unit(points[i], next));
+        let (side, other) = match (before, after) {
+            (Some(before), Some(after)) => (before, after),
+            (Some(only), None) | (None, Some(only)) => (only, only),
+            (None, None) => ((1.0, 0.0), (1.0, 0.0)),
+        };
+        let (dx, dy) = unit(SvgPosition(0.0, 0.0), SvgPosition(side.0 + other.0, side.1 + other.1));
+        let stretch = (dx*side.0 + dy*side.1).max(0.5);
+        (-dy*half/stretch, dx*half/stretch)
+    };
+    let normals: Vec<(f32,f32)> = (0..len).map(normal).collect();
+    let mut left: Vec<SvgPosition> = points.iter().zip(&normals).map(|(p, n)| SvgPosition(p.0 + n.0, p.1 + n.1)).collect();
+    let right = points.iter().zip(&normals).map(|(p, n)| SvgPosition(p.0 - n.0, p.1 - n.1));
+    if closed {
+        vec![left, right.collect()]
+    } else {
+        left.extend(right.rev());
+        vec![left]
+    }
+}
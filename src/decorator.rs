@@ -1,3 +1,4 @@
+use std::f32::consts::PI;
 use std::rc::Rc;
 use std::convert::TryFrom;
 
@@ -5,9 +6,18 @@ use svg::node::element::Line;
 use svg::Document;
 
 use crate::basic;
+use crate::bounds::{BBox, Bounded};
 use crate::ctx::Context;
-use crate::pord::{Cartesian, PordOrCord};
-use crate::utils;
+use crate::pord::{Cartesian, POrd, PordOrCord};
+use crate::utils::{self, LargeArcFlag};
+
+//all in letter radii
+const DOT_RATIO :f32 = 0.12;
+const DOT_DIST_RATIO :f32 = 0.55;
+const LINE_OVERHANG_RATIO :f32 = 0.6;
+//angle between neighbouring dots or lines
+const DOT_SPREAD :f32 = 0.7;
+const LINE_SPREAD :f32 = 0.5;
 
 #[derive(Debug, Clone, Default)]
 pub struct Linebuilder {
@@ -76,6 +86,15 @@ impl Linebuilder {
 }
 
 impl StraightLine {
+    pub fn pord1(&self) -> Rc<PordOrCord> {
+        self.pord1.clone()
+    }
+    pub fn pord2(&self) -> Rc<PordOrCord> {
+        self.pord2.clone()
+    }
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
     pub fn draw(self, doc:Document) -> Document {
         let (x1,y1) = self.pord1.abs_svg_xy(self.ctx.origin());
         let (x2,y2) = self.pord2.abs_svg_xy(self.ctx.origin());
@@ -91,17 +110,39 @@ impl StraightLine {
 impl CirculcarLine {
     pub fn draw_small(self, doc:Document) -> Document {
         //sweep_dir is hardcoded
-        let dist1 = self.pord1.dist_to_sq(self.pord3.as_ref());
-        let dist2 = self.pord2.dist_to_sq(self.pord3.as_ref());
-        let radius = ((dist1 + dist2)/2.0).sqrt();
+        let radius = self.radius();
         basic::arc_small_circle(doc, self.pord1.as_ref(), self.pord2.as_ref(), radius, utils::SweepDirection(false), &self.ctx)
     }
     pub fn draw_big(self, doc:Document) -> Document {
         //sweep_dir is hardcoded
+        let radius = self.radius();
+        basic::arc_big_circle(doc, self.pord1.as_ref(), self.pord2.as_ref(), radius, utils::SweepDirection(false), &self.ctx)
+    }
+    //what draw_small & draw_big would cover
+    pub fn small_bounds(&self) -> BBox {
+        basic::arc_bounds(self.pord1.as_ref(), self.pord2.as_ref(), self.radius(), LargeArcFlag(false), utils::SweepDirection(false), &self.ctx)
+    }
+    pub fn big_bounds(&self) -> BBox {
+        basic::arc_bounds(self.pord1.as_ref(), self.pord2.as_ref(), self.radius(), LargeArcFlag(true), utils::SweepDirection(false), &self.ctx)
+    }
+    fn radius(&self) -> f32 {
         let dist1 = self.pord1.dist_to_sq(self.pord3.as_ref());
         let dist2 = self.pord2.dist_to_sq(self.pord3.as_ref());
-        let radius = ((dist1 + dist2)/2.0).sqrt();
-        basic::arc_big_circle(doc, self.pord1.as_ref(), self.pord2.as_ref(), radius, utils::SweepDirection(false), &self.ctx)
+        ((dist1 + dist2)/2.0).sqrt()
+    }
+}
+
+impl Bounded for StraightLine {
+    fn bounding_box(&self) -> BBox {
+        let ends = [self.pord1.abs_svg_xy(self.ctx.origin()).into(), self.pord2.abs_svg_xy(self.ctx.origin()).into()];
+        BBox::of_points(ends, self.ctx.stroke().strokewidth()/2.0).expect("lines have two ends")
+    }
+}
+
+//it isn't known which way round it'll be drawn, so whichever's bigger
+impl Bounded for CirculcarLine {
+    fn bounding_box(&self) -> BBox {
+        self.small_bounds().union(&self.big_bounds())
     }
 }
 
@@ -137,4 +178,52 @@ impl TryFrom<Linebuilder> for CirculcarLine {
             _ => Err(value.clone())
         }
     }
-}
\ No newline at end of file
+}
+//angles either side of the middle, skipping the middle itself if something's already there
+fn fan(num:u8, spread:f32, avoid_centre:bool) -> Vec<f32> {
+    (0..num).map(|i| if avoid_centre {
+        let side = if i % 2 == 0 {1.0} else {-1.0};
+        side*spread*(1 + i/2) as f32
+    } else {
+        (i as f32 - (num - 1) as f32/2.0)*spread
+    }).collect()
+}
+
+//centres & radius of the dots, just inside the letter on the side facing the word centre
+pub(crate) fn letter_dots(word:&PordOrCord, letter:&Rc<PordOrCord>, radius:f32, num:u8, avoid_centre:bool) -> (Vec<Rc<PordOrCord>>, f32) {
+    let inward = letter.angle_to(word);
+    let dots = fan(num, DOT_SPREAD, avoid_centre).into_iter().map(|offset| {
+        Rc::new(PordOrCord::Pord(POrd::new(DOT_DIST_RATIO*radius, inward + offset, letter.clone())))
+    }).collect();
+    (dots, DOT_RATIO*radius*letter.frame().1)
+}
+
+//lines leave the letter & head away from the word centre until they're a little past the word line.
+//open letters (b & s rows) are bowls open to the outside, so their lines start on the far wall & leave through the mouth.
+//word_radius is on the page, radius is in the letter's own frame
+pub(crate) fn letter_line_stubs(word:&PordOrCord, word_radius:f32, letter:&Rc<PordOrCord>, radius:f32, open:bool, num:u8, avoid_centre:bool) -> Vec<(Rc<PordOrCord>, Rc<PordOrCord>)> {
+    let (rotation, scale) = letter.frame();
+    let overhang = LINE_OVERHANG_RATIO*radius*scale;
+    let end_radius = word_radius + overhang;
+    let outward = letter.angle_to(word) + PI;
+    let (word_x, word_y) = word.abs_svg_xy((0.0, 0.0));
+    let (letter_x, letter_y) = letter.abs_svg_xy((0.0, 0.0));
+    fan(num, LINE_SPREAD, avoid_centre).into_iter().map(|offset| {
+        let (start_angle, heading) = if open {
+            (outward + PI + offset, outward)
+        } else {
+            (outward + offset, outward + offset)
+        };
+        let (start_x, start_y) = (scale*radius*(start_angle + rotation).sin(), scale*radius*(start_angle + rotation).cos());
+        let (dir_x, dir_y) = ((heading + rotation).sin(), (heading + rotation).cos());
+        //how far along the heading until it crosses end_radius, from the word's point of view
+        let (x, y) = (letter_x - word_x + start_x, letter_y - word_y + start_y);
+        let b = x*dir_x + y*dir_y;
+        let c = x*x + y*y - end_radius*end_radius;
+        let dist = (-b + (b*b - c).max(0.0).sqrt()).max(overhang);
+        let (end_r, end_theta) = letter.local_polar((start_x + dist*dir_x, start_y + dist*dir_y));
+        let start = Rc::new(PordOrCord::Pord(POrd::new(radius, start_angle, letter.clone())));
+        let end = Rc::new(PordOrCord::Pord(POrd::new(end_r, end_theta, letter.clone())));
+        (start, end)
+    }).collect()
+}
@@ -0,0 +1,88 @@
+use std::fmt::{Display, Write};
+
+use crate::cutter::Outline;
+use crate::plotter::JOIN_TOLERANCE;
+
+const LAYER :&str = "CUT";
+
+//dxf r12, old enough that just about every laser & cnc program reads it.
+//each cut line is a polyline in mm on the one layer, closed ones flagged as closed
+pub fn dxf(outline:&Outline) -> String {
+    let mut out = String::new();
+    let lines = outline.cut_lines();
+    //the extent of what's cut, not the bed, for programs that place or zoom by it
+    let (min, max) = lines.iter().flatten().fold(None, |extent:Option<((f32,f32),(f32,f32))>, point| Some(match extent {
+        Some((min, max)) => ((min.0.min(point.0), min.1.min(point.1)), (max.0.max(point.0), max.1.max(point.1))),
+        None => (*point, *point),
+    })).unwrap_or_default();
+    code(&mut out, 999, outline.title().unwrap_or("gallifreyan").replace(['\r', '\n'], " "));
+    code(&mut out, 0, "SECTION");
+    code(&mut out, 2, "HEADER");
+    code(&mut out, 9, "$ACADVER");
+    code(&mut out, 1, "AC1009");
+    code(&mut out, 9, "$EXTMIN");
+    point(&mut out, min);
+    code(&mut out, 9, "$EXTMAX");
+    point(&mut out, max);
+    code(&mut out, 0, "ENDSEC");
+    code(&mut out, 0, "SECTION");
+    code(&mut out, 2, "TABLES");
+    code(&mut out, 0, "TABLE");
+    code(&mut out, 2, "LTYPE");
+    code(&mut out, 70, 1);
+    code(&mut out, 0, "LTYPE");
+    code(&mut out, 2, "CONTINUOUS");
+    code(&mut out, 70, 0);
+    code(&mut out, 3, "Solid line");
+    code(&mut out, 72, 65);
+    code(&mut out, 73, 0);
+    code(&mut out, 40, "0.0");
+    code(&mut out, 0, "ENDTAB");
+    code(&mut out, 0, "TABLE");
+    code(&mut out, 2, "LAYER");
+    code(&mut out, 70, 1);
+    code(&mut out, 0, "LAYER");
+    code(&mut out, 2, LAYER);
+    code(&mut out, 70, 0);
+    code(&mut out, 62, 7);
+    code(&mut out, 6, "CONTINUOUS");
+    code(&mut out, 0, "ENDTAB");
+    code(&mut out, 0, "ENDSEC");
+    code(&mut out, 0, "SECTION");
+    code(&mut out, 2, "ENTITIES");
+    for mut line in lines {
+        let closed = line.len() > 2 && {
+            let (first, last) = (line[0], line[line.len() - 1]);
+            (first.0 - last.0).hypot(first.1 - last.1) < JOIN_TOLERANCE
+        };
+        if closed {
+            line.pop();
+        }
+        code(&mut out, 0, "POLYLINE");
+        code(&mut out, 8, LAYER);
+        code(&mut out, 66, 1);
+        point(&mut out, (0.0, 0.0));
+        code(&mut out, 70, if closed {1} else {0});
+        for vertex in line {
+            code(&mut out, 0, "VERTEX");
+            code(&mut out, 8, LAYER);
+            point(&mut out, vertex);
+        }
+        code(&mut out, 0, "SEQEND");
+        code(&mut out, 8, LAYER);
+    }
+    code(&mut out, 0, "ENDSEC");
+    code(&mut out, 0, "EOF");
+    out
+}
+
+//dxf is all group code & value pairs, a line each
+fn code(out:&mut String, code:u16, value:impl Display) {
+    _ = writeln!(out, "{}\n{}", code, value);
+}
+
+fn point(out:&mut String, (x, y):(f32,f32)) {
+    code(out, 10, format!("{:.4}", x));
+    code(out, 20, format!("{:.4}", y));
+    code(out, 30, "0.0");
+}
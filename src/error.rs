@@ -0,0 +1,67 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum GallifreyanError {
+    //the svg origin has to be an absolute Cord
+    InvalidAnchor,
+    //an Rc somewhere up a pord's anchors was dropped, named by whatever the pord belongs to
+    DroppedAnchor(String),
+    //more than one DroppedAnchor, so they can all be fixed at once
+    DroppedAnchors(Vec<GallifreyanError>),
+    AngleOutOfRange{angle:f32, min:f32, max:f32},
+    EmptyWord(String),
+    //no letter in the named word sits on the given pord
+    NoSuchLetter(String),
+    SortDiverged(String),
+    GeometryUnsolvable(String),
+    //bad scene file, unknown ids, anchor loops etc.
+    InvalidScene(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for GallifreyanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GallifreyanError::InvalidAnchor => write!(f, "anchor is not a Cord"),
+            GallifreyanError::DroppedAnchor(owner) => write!(f, "{} hangs off an anchor that was dropped before drawing", owner),
+            GallifreyanError::DroppedAnchors(faults) => {
+                let faults: Vec<String> = faults.iter().map(ToString::to_string).collect();
+                write!(f, "{}", faults.join(", "))
+            }
+            GallifreyanError::AngleOutOfRange{angle, min, max} => {
+                write!(f, "angle {} is outside the arc ({} to {})", angle, min, max)
+            }
+            GallifreyanError::EmptyWord(name) => write!(f, "{} has no letters", name),
+            GallifreyanError::NoSuchLetter(name) => write!(f, "{} has no letter there", name),
+            GallifreyanError::SortDiverged(name) => write!(f, "sorting {} kept looping", name),
+            GallifreyanError::GeometryUnsolvable(msg) => write!(f, "can't solve geometry: {}", msg),
+            GallifreyanError::InvalidScene(msg) => write!(f, "invalid scene: {}", msg),
+            GallifreyanError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for GallifreyanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GallifreyanError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for GallifreyanError {
+    fn from(value: io::Error) -> Self {
+        GallifreyanError::Io(value)
+    }
+}
+
+//a single fault as it is, several together
+pub(crate) fn all_anchors(mut faults:Vec<GallifreyanError>) -> Result<(), GallifreyanError> {
+    match faults.len() {
+        0 => Ok(()),
+        1 => Err(faults.remove(0)),
+        _ => Err(GallifreyanError::DroppedAnchors(faults)),
+    }
+}
@@ -0,0 +1,45 @@
+use std::fmt::Write;
+
+use crate::plotter::{MachineOptions, Plot};
+
+//absolute mm moves, the pen or tool lifted & lowered with z, travelling with G0 at the travel rate & lowering & drawing at the feed rate.
+//the machine stops for a pen change between colours
+pub fn gcode(plot:&Plot, machine:&MachineOptions) -> String {
+    let mut out = String::new();
+    let colours = plot.colours();
+    let up = format!("G0 Z{:.3}", machine.pen_up());
+    _ = writeln!(out, "; {}", plot_name(plot));
+    _ = writeln!(out, "G21 ; mm");
+    _ = writeln!(out, "G90 ; absolute");
+    _ = writeln!(out, "{}", up);
+    let mut pen = None;
+    for (num, points) in plot.machine_lines(machine) {
+        if pen != Some(num) {
+            if pen.is_some() {
+                _ = writeln!(out, "{}", up);
+                _ = writeln!(out, "M0 ; change to the {} pen", colours[num]);
+            } else {
+                _ = writeln!(out, "; {} pen", colours[num]);
+            }
+            pen = Some(num);
+        }
+        let Some(((x, y), rest)) = points.split_first() else {
+            continue
+        };
+        _ = writeln!(out, "G0 X{:.3} Y{:.3} F{:.0}", x, y, machine.travel_rate());
+        //lowered at the feed rate, which the drawing moves then carry on at
+        _ = writeln!(out, "G1 Z{:.3} F{:.0}", machine.pen_down(), machine.feed_rate());
+        for (x, y) in rest {
+            _ = writeln!(out, "G1 X{:.3} Y{:.3}", x, y);
+        }
+        _ = writeln!(out, "{}", up);
+    }
+    _ = writeln!(out, "G0 X0 Y0");
+    _ = writeln!(out, "M2");
+    out
+}
+
+//the svg title if it had one, for the file's opening comment
+fn plot_name(plot:&Plot) -> String {
+    plot.title().unwrap_or("gallifreyan").replace(['\n', ';', '(', ')'], " ")
+}
@@ -0,0 +1,312 @@
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+use crate::pord::{Cartesian, POrd, PordOrCord};
+use crate::utils::{self, LargeArcFlag, PathBuilder, SvgPosition, SweepDirection};
+
+//a circle in svg space, for working out exactly where letters & the word line meet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Disc {
+    centre:SvgPosition,
+    radius:f32,
+}
+//two points either side of the line from a circle's centre towards something, as seen on screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointPair {
+    pub right:SvgPosition,
+    pub left:SvgPosition,
+}
+//a line touching two circles, from where it meets the first to where it meets the second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TangentLine {
+    pub start:SvgPosition,
+    pub end:SvgPosition,
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TangentLines {
+    pub right:TangentLine,
+    pub left:TangentLine,
+}
+//angles at the word centre, from a letter's centre round to where its edges cross the word's.
+//None where they don't cross
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LetterThi {
+    //the word's outer edge & the letter's inner one
+    pub outer:Option<f32>,
+    //the word's inner edge & the letter's outer one
+    pub inner:Option<f32>,
+    //both inner edges
+    pub inner_boundary:Option<f32>,
+    //both outer edges
+    pub outer_boundary:Option<f32>,
+    //at the letter's centre this time, from the word centre round to where its outer edge meets the word's inner one
+    pub theta:Option<f32>,
+}
+
+impl Disc {
+    pub fn new(centre:SvgPosition, radius:f32) -> Disc {
+        Disc { centre, radius }
+    }
+    pub fn centre(&self) -> SvgPosition {
+        self.centre
+    }
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+    pub fn contains(&self, point:SvgPosition) -> bool {
+        dist(self.centre, point) < self.radius
+    }
+    //whether the whole circle fits inside the other one
+    pub fn inside(&self, other:&Disc) -> bool {
+        dist(self.centre, other.centre) + self.radius <= other.radius
+    }
+    //where the edges cross, either side of the line between the centres
+    pub fn crossings(&self, other:&Disc) -> Option<PointPair> {
+        let (right, left) = utils::circle_crossings(self.centre, self.radius, other.centre, other.radius)?;
+        Some(PointPair { right, left })
+    }
+    //the point on the edge at theta, measured the same way as a POrd's
+    pub fn point_at(&self, theta:f32) -> SvgPosition {
+        let (sin, cos) = theta.sin_cos();
+        SvgPosition(self.centre.0 + self.radius*sin, self.centre.1 + self.radius*cos)
+    }
+    //where lines from the point just touch the edge, if it's outside
+    pub fn touch_points(&self, point:SvgPosition) -> Option<PointPair> {
+        let lines = self.tangents(&Disc::new(point, 0.0), 1.0)?;
+        Some(PointPair { right: lines.right.start, left: lines.left.start })
+    }
+    //lines touching both circles without passing between them, if neither is inside the other
+    pub fn outer_tangents(&self, other:&Disc) -> Option<TangentLines> {
+        self.tangents(other, 1.0)
+    }
+    //lines touching both circles that cross between them, if they're apart
+    pub fn inner_tangents(&self, other:&Disc) -> Option<TangentLines> {
+        self.tangents(other, -1.0)
+    }
+    //side is 1 if the other circle touches from the same side of the line as this one, -1 for the far side
+    fn tangents(&self, other:&Disc, side:f32) -> Option<TangentLines> {
+        let (dx, dy) = (other.centre.0 - self.centre.0, other.centre.1 - self.centre.1);
+        let dist = dx.hypot(dy);
+        //how far round from the other centre the touching point sits, as a cosine
+        let cos = (self.radius - side*other.radius)/dist;
+        if dist == 0.0 || cos.abs() > 1.0 {
+            return None
+        }
+        let sin = (1.0 - cos*cos).sqrt();
+        let (ux, uy) = (dx/dist, dy/dist);
+        let line = |turn:f32| {
+            let (nx, ny) = (cos*ux - turn*sin*uy, cos*uy + turn*sin*ux);
+            TangentLine {
+                start: SvgPosition(self.centre.0 + self.radius*nx, self.centre.1 + self.radius*ny),
+                end: SvgPosition(other.centre.0 + side*other.radius*nx, other.centre.1 + side*other.radius*ny),
+            }
+        };
+        Some(TangentLines { right: line(1.0), left: line(-1.0) })
+    }
+    //svg's own angles, clockwise on screen from the x axis
+    fn svg_angle_of(&self, point:SvgPosition) -> f32 {
+        (point.1 - self.centre.1).atan2(point.0 - self.centre.0)
+    }
+    fn svg_point_at(&self, angle:f32) -> SvgPosition {
+        let (sin, cos) = angle.sin_cos();
+        SvgPosition(self.centre.0 + self.radius*cos, self.centre.1 + self.radius*sin)
+    }
+}
+
+//the circle through all three points, None if they're in a line
+pub fn circumcircle(a:SvgPosition, b:SvgPosition, c:SvgPosition) -> Option<Disc> {
+    let (bx, by) = (b.0 - a.0, b.1 - a.1);
+    let (cx, cy) = (c.0 - a.0, c.1 - a.1);
+    let det = 2.0*(bx*cy - by*cx);
+    if det.abs() <= f32::EPSILON*(bx.abs() + by.abs())*(cx.abs() + cy.abs()) {
+        return None
+    }
+    let (b_sq, c_sq) = (bx*bx + by*by, cx*cx + cy*cy);
+    let (x, y) = ((cy*b_sq - by*c_sq)/det, (bx*c_sq - cx*b_sq)/det);
+    Some(Disc::new(SvgPosition(a.0 + x, a.1 + y), x.hypot(y)))
+}
+
+//a pord anchored on anchor that lands on the point, taking the anchor's rotation & scale into account.
+//svg_origin is the one the point was worked out with, it only matters for gords
+pub fn pord_at(anchor:&Rc<PordOrCord>, point:SvgPosition, svg_origin:(f32,f32)) -> POrd {
+    let (x, y) = anchor.abs_svg_xy(svg_origin);
+    let (r, theta) = anchor.local_polar((point.0 - x, point.1 - y));
+    POrd::new(r, theta, anchor.clone())
+}
+
+//the angle opposite c in a triangle with sides a, b & c, all given squared
+pub fn cos_rule_angle_c(a_dist_sq:f32,b_dist_sq:f32,c_dist_sq:f32) -> Option<f32> {
+    let top = a_dist_sq + b_dist_sq - c_dist_sq;
+    let bot = a_dist_sq.sqrt()*(b_dist_sq.sqrt())*2.0;
+    thi_check(top, bot)
+}
+
+fn thi_check(top:f32,bot:f32) -> Option<f32> {
+    if top.abs() <= bot {
+        Some((top/bot).acos())
+    } else {None}
+}
+
+//dist_sq is between the centres, the radii are each circle's (inner, outer) edges
+pub fn letter_thi(dist_sq:f32, word_radii:(f32,f32), letter_radii:(f32,f32)) -> LetterThi {
+    let (word_r_i_sq, word_r_o_sq) = (word_radii.0.powi(2), word_radii.1.powi(2));
+    let (lett_r_i_sq, lett_r_o_sq) = (letter_radii.0.powi(2), letter_radii.1.powi(2));
+    LetterThi {
+        outer: cos_rule_angle_c(dist_sq, word_r_o_sq, lett_r_i_sq),
+        inner: cos_rule_angle_c(dist_sq, word_r_i_sq, lett_r_o_sq),
+        inner_boundary: cos_rule_angle_c(dist_sq, word_r_i_sq, lett_r_i_sq),
+        outer_boundary: cos_rule_angle_c(dist_sq, word_r_o_sq, lett_r_o_sq),
+        theta: cos_rule_angle_c(dist_sq, lett_r_o_sq, word_r_i_sq),
+    }
+}
+
+pub fn dist(p1:SvgPosition, p2:SvgPosition) -> f32 {
+    (p2.0 - p1.0).hypot(p2.1 - p1.1)
+}
+
+//arcs round the disc to `to`, going whichever way passes through the points `keep` wants
+pub(crate) fn arc_through(path:&mut PathBuilder, disc:&Disc, from:SvgPosition, to:SvgPosition, keep:impl Fn(SvgPosition) -> bool) {
+    let start = disc.svg_angle_of(from);
+    let span = (disc.svg_angle_of(to) - start).rem_euclid(2.0*PI);
+    //svg's positive angle direction is clockwise on screen
+    let sweep = keep(disc.svg_point_at(start + span/2.0));
+    let long = if sweep {span} else {2.0*PI - span} > PI;
+    path.arc_to(to, disc.radius, LargeArcFlag(long), SweepDirection(sweep));
+}
+
+//the whole circle as a contour of its own
+pub(crate) fn full_circle(path:&mut PathBuilder, disc:&Disc) {
+    let start = disc.svg_point_at(0.0);
+    path.move_to(start);
+    path.arc_to(disc.svg_point_at(PI), disc.radius, LargeArcFlag(false), SweepDirection(true));
+    path.arc_to(start, disc.radius, LargeArcFlag(false), SweepDirection(true));
+}
+
+//contours for the gap between two nested letter circles, as far as the word's inner boundary.
+//`outer` & `inner` are the edges facing the gap, so the inner edge of the bigger letter & the outer edge of the smaller.
+//filled evenodd, any smaller circle left floating inside becomes a hole
+pub(crate) fn nested_gap(path:&mut PathBuilder, outer:&Disc, inner:&Disc, bound:&Disc) {
+    let in_gap = |p:SvgPosition| outer.contains(p) && !inner.contains(p);
+    match (outer.crossings(bound), inner.crossings(bound)) {
+        (Some(PointPair{right:oa, left:ob}), Some(PointPair{right:ia, left:ib})) => {
+            //pair each end of the outer edge with the nearer end of the inner one
+            let (ia, ib) = if dist(ob, ib) <= dist(ob, ia) {(ia, ib)} else {(ib, ia)};
+            path.move_to(oa);
+            arc_through(path, outer, oa, ob, |p| bound.contains(p));
+            arc_through(path, bound, ob, ib, in_gap);
+            arc_through(path, inner, ib, ia, |p| bound.contains(p));
+            arc_through(path, bound, ia, oa, in_gap);
+        }
+        (Some(PointPair{right:oa, left:ob}), None) => {
+            path.move_to(oa);
+            arc_through(path, outer, oa, ob, |p| bound.contains(p));
+            arc_through(path, bound, ob, oa, |p| outer.contains(p));
+            if inner.inside(bound) {
+                full_circle(path, inner);
+            }
+        }
+        (None, None) if outer.inside(bound) => {
+            full_circle(path, outer);
+            full_circle(path, inner);
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pord::Polar;
+
+    fn assert_near(a:SvgPosition, b:SvgPosition) {
+        assert!(dist(a, b) < 1e-3, "{:?} isn't {:?}", a, b);
+    }
+
+    fn assert_angle(a:Option<f32>, b:f32) {
+        let a = a.expect("they cross");
+        assert!((a - b).abs() < 1e-4, "{} isn't {}", a, b);
+    }
+
+    #[test]
+    fn crossings() {
+        let disc = Disc::new(SvgPosition(0.0, 0.0), 5.0);
+        let pair = disc.crossings(&Disc::new(SvgPosition(8.0, 0.0), 5.0)).expect("they overlap");
+        //y goes down the screen, so right of the way to the other centre is +y
+        assert_near(pair.right, SvgPosition(4.0, 3.0));
+        assert_near(pair.left, SvgPosition(4.0, -3.0));
+        //too far apart, one inside the other & the same centre
+        assert!(disc.crossings(&Disc::new(SvgPosition(20.0, 0.0), 5.0)).is_none());
+        assert!(disc.crossings(&Disc::new(SvgPosition(1.0, 0.0), 1.0)).is_none());
+        assert!(disc.crossings(&Disc::new(SvgPosition(0.0, 0.0), 3.0)).is_none());
+    }
+
+    #[test]
+    fn tangents() {
+        let small = Disc::new(SvgPosition(0.0, 0.0), 2.0);
+        let outer = small.outer_tangents(&Disc::new(SvgPosition(10.0, 0.0), 2.0)).expect("they're apart");
+        assert_near(outer.right.start, SvgPosition(0.0, 2.0));
+        assert_near(outer.right.end, SvgPosition(10.0, 2.0));
+        assert_near(outer.left.start, SvgPosition(0.0, -2.0));
+        assert_near(outer.left.end, SvgPosition(10.0, -2.0));
+        //3 4 5 triangles either side, crossing halfway
+        let disc = Disc::new(SvgPosition(0.0, 0.0), 3.0);
+        let inner = disc.inner_tangents(&Disc::new(SvgPosition(10.0, 0.0), 3.0)).expect("they're apart");
+        assert_near(inner.right.start, SvgPosition(1.8, 2.4));
+        assert_near(inner.right.end, SvgPosition(8.2, -2.4));
+        assert_near(inner.left.start, SvgPosition(1.8, -2.4));
+        assert_near(inner.left.end, SvgPosition(8.2, 2.4));
+        //overlapping circles only have outer ones, nested circles have neither
+        let overlapping = Disc::new(SvgPosition(4.0, 0.0), 3.0);
+        assert!(disc.inner_tangents(&overlapping).is_none());
+        assert!(disc.outer_tangents(&overlapping).is_some());
+        assert!(disc.outer_tangents(&Disc::new(SvgPosition(0.5, 0.0), 1.0)).is_none());
+    }
+
+    #[test]
+    fn touch_points() {
+        let disc = Disc::new(SvgPosition(0.0, 0.0), 3.0);
+        let pair = disc.touch_points(SvgPosition(5.0, 0.0)).expect("it's outside");
+        assert_near(pair.right, SvgPosition(1.8, 2.4));
+        assert_near(pair.left, SvgPosition(1.8, -2.4));
+        assert!(disc.touch_points(SvgPosition(1.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn circumcircle_through_three() {
+        let disc = circumcircle(SvgPosition(0.0, 0.0), SvgPosition(4.0, 0.0), SvgPosition(0.0, 4.0)).expect("not in a line");
+        assert_near(disc.centre(), SvgPosition(2.0, 2.0));
+        assert!((disc.radius() - 8.0f32.sqrt()).abs() < 1e-4);
+        assert!(circumcircle(SvgPosition(0.0, 0.0), SvgPosition(1.0, 1.0), SvgPosition(3.0, 3.0)).is_none());
+    }
+
+    #[test]
+    fn pord_at_round_trip() {
+        let svg_origin = (500.0, 500.0);
+        let origin = PordOrCord::gal_origin(svg_origin);
+        let anchor = Rc::new(PordOrCord::Pord(POrd::new(100.0, 0.7, origin)));
+        let frame = anchor.as_pord().expect("it's a pord");
+        frame.set_rotation(PI/2.0);
+        frame.set_scale(2.0);
+        let (x, y) = anchor.abs_svg_xy(svg_origin);
+        //40 to the right is a quarter turn on, which the rotation undoes, & half as far
+        let pord = pord_at(&anchor, SvgPosition(x + 40.0, y), svg_origin);
+        assert!((pord.r() - 20.0).abs() < 1e-3, "r is {}", pord.r());
+        assert!(pord.theta().sin().abs() < 1e-4 && pord.theta().cos() > 0.0, "theta is {}", pord.theta());
+        for point in [SvgPosition(400.0, 620.0), SvgPosition(x - 3.0, y + 7.0)] {
+            let pord = PordOrCord::Pord(pord_at(&anchor, point, svg_origin));
+            assert_near(pord.abs_svg_xy(svg_origin).into(), point);
+        }
+    }
+
+    #[test]
+    fn letter_thi_angles() {
+        //centres 4 apart, word edges at 3 & letter edges at 5 make a 3 4 5 triangle, square at the word centre
+        let thi = letter_thi(16.0, (3.0, 3.0), (5.0, 5.0));
+        for angle in [thi.outer, thi.inner, thi.inner_boundary, thi.outer_boundary] {
+            assert_angle(angle, PI/2.0);
+        }
+        assert_angle(thi.theta, 0.6f32.asin());
+        //a letter nowhere near the line
+        assert_eq!(letter_thi(100.0, (3.0, 3.0), (1.0, 1.0)), LetterThi::default());
+    }
+}
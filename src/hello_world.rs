@@ -1,5 +1,6 @@
 use std::f32::consts::PI;
 use std::io::Error;
+use std::path::Path;
 use std::rc::Rc;
 
 use svg::Document;
@@ -9,18 +10,18 @@ use gallifreyan as Gal;
 
 use Gal::ctx::{Context, ColourContext, StrokeContext};
 use Gal::pord::{POrd, PordOrCord};
-use Gal::{basic, decorator, word::{self, Word}, StemType};
-use Gal::{pord_vec2dot,pord_from_vec_pop,poc_rc};
+use Gal::{basic, decorator, word::{self, Word}, Decoration, StemType};
+use Gal::translate::VowelKind;
+use Gal::{pord_from_vec_pop,poc_rc};
 
 const WIDTH: u64 = 2048;
 const HEIGHT:u64 = 2048;
-const DOT_RADIUS    :f32 = 30.0;
 const VOWEL_RADIUS  :f32 = 40.0;
 const LETTER_RADIUS :f32 = 120.0;
 
 pub fn hello_world() -> Result<Document, Error> {
     let filename = "hello_world";
-    let filepath = "Imgs\\".to_owned() + &filename.trim();
+    let filepath = Path::new("Imgs").join(filename.trim()).display().to_string();
     println!("Initialising...");
     let (mut doc, svg_origin) = Gal::canvas_init(WIDTH, HEIGHT, "white");
     let origin = PordOrCord::gal_origin(svg_origin);
@@ -43,36 +44,27 @@ pub fn hello_world() -> Result<Document, Error> {
         w_ctx.clone()
     );
     let (h_pord,mut h_points) = hello.new_letter_with_attach(315.0, 0.0, LETTER_RADIUS, StemType::B, Some(l_ctx.clone()), 2);
-    hello.new_letter_from_pordorcord(h_pord.clone(), VOWEL_RADIUS, StemType::J, Some(v_ctx.clone()), 0);
-    let (l_pord,l_points) = hello.new_letter_with_attach(150.0, PI, LETTER_RADIUS, StemType::J, Some(l_ctx.clone()), 3);
-    let mut o_pord_vec = hello.new_letter_from_pordorcord(l_pord.clone(), LETTER_RADIUS + 70.0, StemType::J, Some(l_ctx.clone()), 1);
-    let o_pord = o_pord_vec.pop().unwrap();
-    hello.new_letter_from_pord(o_pord, VOWEL_RADIUS, StemType::J, Some(v_ctx.clone()), 0);
+    hello.attach_vowel(&h_pord, VowelKind::E, VOWEL_RADIUS, Some(v_ctx.clone())).map_err(Error::other)?;
+    let l_pord = hello.new_letter_from_data(150.0, PI, LETTER_RADIUS, StemType::J, Some(l_ctx.clone()));
+    hello.decorate(&l_pord, Decoration::Dots(3)).map_err(Error::other)?;
+    hello.new_letter_from_pordorcord(l_pord.clone(), LETTER_RADIUS + 70.0, StemType::J, Some(l_ctx.clone()), 0);
+    hello.attach_vowel(&l_pord, VowelKind::O, VOWEL_RADIUS, Some(v_ctx.clone())).map_err(Error::other)?;
     
     let world_pord = poc_rc!(450.0, PI, origin.clone());
     let mut world = word::WordCircle::new("world",world_pord.clone(),400.0,w_ctx);
-    let (_w_pord,mut w_points) = world.new_letter_with_attach(400.0, 0.0, LETTER_RADIUS, StemType::S, None, 3);
-    let mut new_o = w_points.pop().unwrap();
-    new_o.set_theta(PI);
-    world.new_letter_from_pord(new_o.clone(), VOWEL_RADIUS, StemType::J, Some(v_ctx.clone()),0);
-    //We're not going to need any of these pords
-    let (_r_pord,r_points) = world.new_letter_with_attach(400.0, PI/2.0, LETTER_RADIUS, StemType::S, None, 3);
-    let (_new_l_pord,new_l_points) = world.new_letter_with_attach(220.0, PI, LETTER_RADIUS, StemType::J, Some(l_ctx.clone()), 3);
-    let (_d_pord,d_points) = world.new_letter_with_attach(315.0, PI*1.5, LETTER_RADIUS, StemType::B, Some(l_ctx.clone()), 3);
+    let (w_pord,mut w_points) = world.new_letter_with_attach(400.0, 0.0, LETTER_RADIUS, StemType::S, None, 2);
+    world.attach_vowel(&w_pord, VowelKind::O, VOWEL_RADIUS, Some(v_ctx.clone())).map_err(Error::other)?;
+    let r_pord = world.new_letter_from_data(400.0, PI/2.0, LETTER_RADIUS, StemType::S, None);
+    let new_l_pord = world.new_letter_from_data(220.0, PI, LETTER_RADIUS, StemType::J, Some(l_ctx.clone()));
+    let d_pord = world.new_letter_from_data(315.0, PI*1.5, LETTER_RADIUS, StemType::B, Some(l_ctx.clone()));
+    for letter in [r_pord, new_l_pord, d_pord] {
+        world.decorate(&letter, Decoration::Dots(3)).map_err(Error::other)?;
+    }
     
-    println!("Drawing word arcs...");
+    println!("Drawing words...");
     doc = hello.draw(doc);
     doc = world.draw(doc);
 
-    println!("Drawing dots...");
-    let filled = ColourContext::default_path();
-    let strokeless = StrokeContext::new(0.0);
-    let path_ctx = Context::new(filled,strokeless,origin.clone());
-    pord_vec2dot!(l_points,-65.0,DOT_RADIUS, &path_ctx,doc);
-    pord_vec2dot!(d_points,-65.0,DOT_RADIUS, &path_ctx,doc);
-    pord_vec2dot!(r_points,-65.0,DOT_RADIUS, &path_ctx,doc);
-    pord_vec2dot!(new_l_points,-65.0,DOT_RADIUS, &path_ctx,doc);
-    
     println!("Drawing lines...");
     let line_ctx = prime_ctx.new_strokewidth(10.0);
     let mut first = Linebuilder::new(&line_ctx);
@@ -0,0 +1,35 @@
+use std::fmt::Write;
+
+use crate::plotter::{MachineOptions, Plot};
+
+//hpgl plotter units
+const UNITS_PER_MM :f32 = 40.0;
+
+//pen numbers follow the plot's colours, from 1.
+//hpgl speeds are in cm/s, so the feed rate gets converted
+pub fn hpgl(plot:&Plot, machine:&MachineOptions) -> String {
+    let mut out = String::new();
+    let units = |value:f32| (value*UNITS_PER_MM).round() as i32;
+    _ = write!(out, "IN;");
+    _ = write!(out, "VS{:.1};", machine.feed_rate()/600.0);
+    let mut pen = None;
+    for (num, points) in plot.machine_lines(machine) {
+        if pen != Some(num) {
+            _ = write!(out, "\nSP{};", num + 1);
+            pen = Some(num);
+        }
+        let Some(((x, y), rest)) = points.split_first() else {
+            continue
+        };
+        _ = write!(out, "\nPU{},{};", units(*x), units(*y));
+        if rest.is_empty() {
+            //a dot, pen down where it is
+            _ = write!(out, "PD;");
+            continue
+        }
+        let coords: Vec<String> = rest.iter().map(|(x, y)| format!("{},{}", units(*x), units(*y))).collect();
+        _ = write!(out, "PD{};", coords.join(","));
+    }
+    _ = writeln!(out, "\nPU;SP0;");
+    out
+}
@@ -0,0 +1,216 @@
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+use crate::ctx::Context;
+use crate::error::GallifreyanError;
+use crate::pord::PordOrCord;
+use crate::translate::{self, Glyph, VowelKind};
+use crate::vowel;
+use crate::word::{Word, WordCircle};
+use crate::{Decoration, StemType};
+
+const MAX_LETTER_RATIO :f32 = 0.3;
+const VOWEL_RATIO :f32 = 0.35;
+const LETTER_GAP_RATIO :f32 = 0.04;
+const B_OVERLAP :f32 = 0.75;
+//B letters have to poke through the whole line to cut it
+const B_STROKE_OVERLAP :f32 = 1.5;
+const J_DEPTH :f32 = 1.25;
+pub(crate) const SHRINK_STEP :f32 = 0.95;
+pub(crate) const MAX_FIT_ATTEMPTS :usize = 200;
+
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub enum Spacing {
+    #[default]
+    Even,
+    Weighted,
+}
+#[derive(Debug, Clone, Copy)]
+pub struct WordLayout {
+    spacing:Spacing,
+    start_angle:f32,
+    max_letter_ratio:f32,
+    gap_ratio:f32,
+}
+//a consonant & its decoration with the vowel that follows it, or a vowel on its own
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Slot {
+    Consonant(StemType, Decoration, Option<VowelKind>),
+    Vowel(VowelKind),
+}
+//distance from the word centre, angle & radius of a letter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Placement {
+    pub dist:f32,
+    pub theta:f32,
+    pub radius:f32,
+}
+
+impl Default for WordLayout {
+    fn default() -> Self {
+        Self {
+            spacing: Spacing::Even,
+            start_angle: 0.0,
+            max_letter_ratio: MAX_LETTER_RATIO,
+            gap_ratio: LETTER_GAP_RATIO,
+        }
+    }
+}
+
+impl WordLayout {
+    pub fn new(spacing:Spacing) -> WordLayout {
+        WordLayout { spacing, ..Default::default() }
+    }
+    pub fn set_start_angle(&mut self, angle:f32) {
+        self.start_angle = angle;
+    }
+    //largest letter radius, as a fraction of the word radius
+    pub fn set_max_letter_ratio(&mut self, ratio:f32) {
+        self.max_letter_ratio = ratio;
+    }
+    //smallest gap between letters, as a fraction of the word radius
+    pub fn set_gap_ratio(&mut self, ratio:f32) {
+        self.gap_ratio = ratio;
+    }
+    //fails if the letters can't be shrunk enough to fit round the word
+    pub fn word_circle(&self, name:&str, pord:Rc<PordOrCord>, radius:f32, stems:&[StemType], ctx:Context) -> Result<WordCircle, GallifreyanError> {
+        let slots: Vec<Slot> = stems.iter().map(|stem| Slot::Consonant(*stem, Decoration::None, None)).collect();
+        self.populate(WordCircle::new(name, pord, radius, ctx), &slots, &[])
+    }
+    pub fn word_circle_from_glyphs(&self, name:&str, pord:Rc<PordOrCord>, radius:f32, glyphs:&[Glyph], ctx:Context) -> Result<WordCircle, GallifreyanError> {
+        let (slots, texts): (Vec<Slot>, Vec<String>) = slots(glyphs).into_iter().unzip();
+        let mut word = WordCircle::new(name, pord, radius, ctx);
+        word.set_transliteration(&translate::transliterate(glyphs));
+        self.populate(word, &slots, &texts)
+    }
+    pub fn word_circle_from_text(&self, word:&str, pord:Rc<PordOrCord>, radius:f32, ctx:Context) -> Result<WordCircle, GallifreyanError> {
+        self.word_circle_from_glyphs(word, pord, radius, &translate::translate_word(word), ctx)
+    }
+    //texts are what each slot was written as, if known
+    fn populate(&self, mut word:WordCircle, slots:&[Slot], texts:&[String]) -> Result<WordCircle, GallifreyanError> {
+        let word_radius = word.radius();
+        let stroke = word.ctx().stroke().strokewidth();
+        let name = word.name().to_string();
+        let unsolvable = || GallifreyanError::GeometryUnsolvable(format!("letters don't fit round {}", name));
+        let placed = self.place(slots, word_radius, stroke).ok_or_else(unsolvable)?;
+        for (i, (slot, place)) in slots.iter().zip(placed).enumerate() {
+            match *slot {
+                Slot::Consonant(stem_type, decoration, vowel) => {
+                    let letter = word.new_letter_from_data(place.dist, place.theta, place.radius, stem_type, None);
+                    word.decorate(&letter, decoration).expect("letter was just added");
+                    if let Some(text) = texts.get(i) {
+                        word.set_letter_text(&letter, text).expect("letter was just added");
+                    }
+                    if let Some(kind) = vowel {
+                        word.attach_vowel(&letter, kind, VOWEL_RATIO * place.radius, None).expect("letter was just added");
+                    }
+                }
+                Slot::Vowel(kind) => {
+                    word.new_vowel(kind, place.theta, place.radius, None);
+                }
+            }
+        }
+        //shrunk too far to break through the word line
+        if word.arcs().clone().iter().any(|letter| !word.crosses_line(letter)) {
+            return Err(unsolvable())
+        }
+        Ok(word)
+    }
+    //shrinks the letters until no two neighbours overlap, None if they still do after every try
+    fn place(&self, slots:&[Slot], word_radius:f32, stroke:f32) -> Option<Vec<Placement>> {
+        let weights: Vec<f32> = slots.iter().map(|slot| slot.weight()).collect();
+        let angles = self.angles(&weights);
+        let gap = self.gap_ratio * word_radius;
+        let mut unit = self.max_letter_ratio * word_radius;
+        for _ in 0..MAX_FIT_ATTEMPTS {
+            let placed: Vec<Placement> = slots.iter().zip(&weights).zip(&angles).map(|((slot, weight), theta)| {
+                let radius = weight * unit;
+                Placement { dist: slot.dist(word_radius, radius, stroke), theta: *theta, radius }
+            }).collect();
+            if ring_fits(&placed, gap) {
+                return Some(placed)
+            }
+            unit *= SHRINK_STEP;
+        }
+        None
+    }
+    fn angles(&self, weights:&[f32]) -> Vec<f32> {
+        let num = weights.len();
+        match self.spacing {
+            Spacing::Even => (0..num).map(|i| self.start_angle + 2.0*PI*i as f32/num as f32).collect(),
+            Spacing::Weighted => weighted_angles(weights, self.start_angle),
+        }
+    }
+}
+
+impl Slot {
+    fn weight(&self) -> f32 {
+        match self {
+            Slot::Consonant(stem_type, ..) => stem_weight(*stem_type),
+            Slot::Vowel(_) => VOWEL_RATIO,
+        }
+    }
+    fn dist(&self, word_radius:f32, radius:f32, stroke:f32) -> f32 {
+        match self {
+            Slot::Consonant(stem_type, ..) => stem_dist(*stem_type, word_radius, radius, stroke),
+            Slot::Vowel(kind) => vowel::standalone_dist(*kind, word_radius, radius, stroke),
+        }
+    }
+}
+
+//J & Z letters sit inside or across the line, so they can be a touch smaller
+fn stem_weight(stem_type:StemType) -> f32 {
+    match stem_type {
+        StemType::B | StemType::S => 1.0,
+        StemType::J => 0.85,
+        StemType::Z => 0.75,
+    }
+}
+
+//B letters break the line from inside, J are fully inside, S & Z sit on the line
+fn stem_dist(stem_type:StemType, word_radius:f32, radius:f32, stroke:f32) -> f32 {
+    match stem_type {
+        StemType::B => word_radius - radius + ((1.0 - B_OVERLAP)*radius).max(B_STROKE_OVERLAP*stroke),
+        StemType::J => word_radius - J_DEPTH*radius,
+        StemType::S | StemType::Z => word_radius,
+    }
+}
+
+//groups each consonant with the vowel straight after it, along with the text they came from
+fn slots(glyphs:&[Glyph]) -> Vec<(Slot, String)> {
+    let mut result: Vec<(Slot, String)> = Vec::with_capacity(glyphs.len());
+    for glyph in glyphs {
+        match (glyph.vowel_kind(), result.last_mut()) {
+            (Some(kind), Some((Slot::Consonant(_, _, vowel @ None), text))) => {
+                *vowel = Some(kind);
+                text.push_str(glyph.text());
+            }
+            (Some(kind), _) => result.push((Slot::Vowel(kind), glyph.text().to_string())),
+            (None, _) => result.push((Slot::Consonant(glyph.stem_type().expect("not a vowel"), glyph.decoration(), None), glyph.text().to_string())),
+        }
+    }
+    result
+}
+
+//each angle gets a share of the circle proportional to its weight
+pub(crate) fn weighted_angles(weights:&[f32], start_angle:f32) -> Vec<f32> {
+    let total: f32 = weights.iter().sum();
+    let mut before = 0.0;
+    weights.iter().map(|w| {
+        //first item is centred on the start angle
+        let theta = start_angle + 2.0*PI*(before + (w - weights[0])/2.0)/total;
+        before += w;
+        theta
+    }).collect()
+}
+
+pub(crate) fn ring_fits(placed:&[Placement], gap:f32) -> bool {
+    if placed.len() < 2 {
+        return true
+    }
+    let pairs = placed.iter().zip(placed.iter().cycle().skip(1));
+    pairs.take(placed.len()).all(|(a, b)| {
+        let dist_sq = a.dist.powi(2) + b.dist.powi(2) - 2.0*a.dist*b.dist*(a.theta - b.theta).cos();
+        dist_sq >= (a.radius + b.radius + gap).powi(2)
+    })
+}
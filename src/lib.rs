@@ -1,28 +1,49 @@
 use std::io::Error;
 
+use serde::{Deserialize, Serialize};
 use svg::Document;
 
 
+pub mod error;
+pub mod canvas;
 pub mod ctx;
 pub mod pord;
 pub mod basic;
 pub mod decorator;
 pub mod word;
+pub mod geometry;
+pub mod vowel;
 pub mod utils;
+pub mod translate;
+pub mod layout;
+pub mod sentence;
+pub mod raster;
+pub mod plotter;
+pub mod gcode;
+pub mod hpgl;
+pub mod cutter;
+pub mod dxf;
+pub mod stl;
+pub mod bounds;
+pub mod scene;
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub const BACKGROUND_ID :&str = "background";
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StemType {B,J,S,Z}
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Decoration {
+    #[default]
+    None,
+    Dots(u8),
+    Lines(u8),
+}
 
+//see canvas::Canvas for margins, transparency & other backgrounds
 pub fn canvas_init(width:u64, height:u64, solid_background:&str) -> (Document, (f32,f32)) {
-    let drawn = Document::new().set("viewBox", (0, 0, width, height));   
-    let background = svg::node::element::Rectangle::new()
-    .set("x", 0)
-    .set("y", 0)
-    .set("width", width)
-    .set("height", height)
-    .set("fill", solid_background)
-    .set("stroke", "none");
-    (drawn.add(background), ((width/2) as f32,(height/2) as f32))
+    let mut canvas = canvas::Canvas::new(width as f32, height as f32);
+    canvas.set_background(canvas::Background::solid(solid_background));
+    canvas.build()
 }
 
 pub fn save(filepath: String, doc:&Document) -> Result<(), Error> {
@@ -31,6 +52,20 @@ pub fn save(filepath: String, doc:&Document) -> Result<(), Error> {
     svg::save(filename, doc)
 }
 
+pub fn try_save(filepath: String, doc:&Document) -> Result<(), error::GallifreyanError> {
+    Ok(save(filepath, doc)?)
+}
+
+pub fn save_png(filepath: String, doc:&Document, scale:f32) -> Result<(), Error> {
+    save_png_with_options(filepath, doc, &raster::PngOptions::new(raster::PngSize::Scale(scale)))
+}
+
+pub fn save_png_with_options(filepath: String, doc:&Document, options:&raster::PngOptions) -> Result<(), Error> {
+    let filename = filepath + ".png";
+    println!("Saving under {}", filename);
+    std::fs::write(filename, raster::render_png_bytes(doc, options)?)
+}
+
 pub fn svg_str(doc:&Document) -> String {
     Document::to_string(doc)
 }
@@ -1,10 +1,14 @@
 use std::io::Error;
 
+mod cli;
 mod test;
 //mod hello_world;
 
 fn main() -> Result<(), Error> {
     //_ = hello_world::hello_world()?;
-    test::test("test");
+    if let Err(err) = cli::run(std::env::args().skip(1)) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
     Ok(())
 }
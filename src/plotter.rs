@@ -0,0 +1,457 @@
+use std::io::Error;
+
+use svg::Document;
+use svg::node::Value;
+use svg::node::element::path::{Command, Data, Position};
+use svg::node::element::{Description, Group, Path, Title};
+use svg::node::element::tag::Type;
+use svg::parser::Event;
+
+use crate::BACKGROUND_ID;
+use crate::geometry::dist;
+use crate::utils::{LargeArcFlag, PathBuilder, SvgPosition, SweepDirection};
+
+//ends closer than this count as joined
+pub(crate) const JOIN_TOLERANCE :f32 = 0.01;
+//bed size & speeds in mm & mm/min, an a4 sheet on a small plotter
+const DEFAULT_BED :(f32,f32) = (297.0, 210.0);
+const DEFAULT_TOLERANCE :f32 = 0.1;
+const DEFAULT_FEED_RATE :f32 = 1500.0;
+const DEFAULT_TRAVEL_RATE :f32 = 3000.0;
+const DEFAULT_PEN_HEIGHTS :(f32,f32) = (5.0, 0.0);
+
+//something the pen draws without lifting
+#[derive(Debug, Clone)]
+enum Shape {
+    //can start anywhere round it
+    Circle{centre:SvgPosition, radius:f32},
+    Open(PathBuilder),
+}
+#[derive(Debug, Clone)]
+struct Stroke {
+    shape:Shape,
+    colour:String,
+    width:f32,
+}
+//a single pen down run, in svg units
+#[derive(Debug, Clone)]
+pub struct PenPath {
+    colour:String,
+    width:f32,
+    path:PathBuilder,
+}
+//a drawing broken down into single strokes, grouped by colour & ordered so the pen travels as little as possible lifted
+#[derive(Debug, Clone)]
+pub struct Plot {
+    view_box:(f32,f32,f32,f32),
+    //the svg's own attributes & its (tag, id, text) title & desc, for writing it back out
+    attributes:Vec<(String,Value)>,
+    labels:Vec<(String,String,String)>,
+    paths:Vec<PenPath>,
+    //pen up travel in the order things were drawn, & after sorting
+    travel:(f32,f32),
+}
+//where a plot gets drawn, sizes in mm & speeds in mm/min
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MachineOptions {
+    width:f32,
+    height:f32,
+    tolerance:f32,
+    feed_rate:f32,
+    travel_rate:f32,
+    pen_up:f32,
+    pen_down:f32,
+}
+
+impl Stroke {
+    //where the pen would go down & come up again, starting as close to `pen` as it can
+    fn ends(&self, pen:SvgPosition) -> (SvgPosition, SvgPosition) {
+        match &self.shape {
+            Shape::Circle{centre, radius} => {
+                let angle = (pen.1 - centre.1).atan2(pen.0 - centre.0);
+                let start = SvgPosition(centre.0 + radius*angle.cos(), centre.1 + radius*angle.sin());
+                (start, start)
+            }
+            Shape::Open(path) => {
+                let (first, last) = (path.first_position(), path.last_position());
+                (first.expect("no empty strokes"), last.expect("no empty strokes"))
+            }
+        }
+    }
+    fn closed(&self) -> bool {
+        let (start, end) = self.ends(SvgPosition(0.0, 0.0));
+        dist(start, end) < JOIN_TOLERANCE
+    }
+    fn into_pen_path(self, pen:SvgPosition, reversed:bool) -> PenPath {
+        let path = match self.shape {
+            Shape::Circle{centre, radius} => {
+                let (start, _) = self.ends(pen);
+                let opposite = SvgPosition(2.0*centre.0 - start.0, 2.0*centre.1 - start.1);
+                let mut path = PathBuilder::new();
+                path.move_to(start);
+                path.arc_to(opposite, radius, LargeArcFlag(false), SweepDirection(true));
+                path.arc_to(start, radius, LargeArcFlag(false), SweepDirection(true));
+                path
+            }
+            Shape::Open(path) if reversed => path.reversed(),
+            Shape::Open(path) => path,
+        };
+        PenPath { colour: self.colour, width: self.width, path }
+    }
+}
+
+impl PenPath {
+    pub fn colour(&self) -> &str {
+        &self.colour
+    }
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    pub fn path(&self) -> &PathBuilder {
+        &self.path
+    }
+}
+
+impl Plot {
+    //draw the document with centre lines on first, see ctx::Context::set_centre_lines.
+    //every circle, line & path becomes single strokes, filled circles become spirals `pen_width` apart.
+    //backgrounds & anything that's neither stroked nor filled are left out
+    pub fn new(doc:&Document, pen_width:f32) -> Result<Plot, Error> {
+        let content = doc.to_string();
+        let mut attributes = Vec::new();
+        let mut labels = Vec::new();
+        let mut strokes = Vec::new();
+        //title & desc, waiting for their text
+        let mut label: Option<(String, String)> = None;
+        for event in svg::read(&content)? {
+            match event {
+                Event::Error(err) => return Err(Error::other(err)),
+                Event::Tag("svg", Type::Start, attrs) => {
+                    attributes.extend(attrs.into_iter().filter(|(name, _)| name.as_str() != "xmlns"));
+                }
+                Event::Tag(tag @ ("title" | "desc"), Type::Start, attrs) => {
+                    label = Some((tag.to_string(), attrs.get("id").map(|id| id.to_string()).unwrap_or_default()));
+                }
+                Event::Text(text) => {
+                    if let Some((tag, id)) = label.take() {
+                        labels.push((tag, id, text.to_string()));
+                    }
+                }
+                Event::Tag(tag, _, attrs) => {
+                    let get = |name:&str| attrs.get(name).map(|value| value.to_string());
+                    let num = |name:&str| get(name).and_then(|value| value.parse::<f32>().ok()).unwrap_or(0.0);
+                    if get("id").as_deref() == Some(BACKGROUND_ID) {
+                        continue
+                    }
+                    let stroke = get("stroke").filter(|colour| colour != "none");
+                    let fill = get("fill").filter(|colour| colour != "none");
+                    let (colour, width) = match (stroke, fill) {
+                        (Some(colour), _) => (colour, num("stroke-width")),
+                        (None, Some(colour)) => (colour, pen_width),
+                        (None, None) => continue,
+                    };
+                    let shapes = match tag {
+                        "circle" => {
+                            let (centre, radius) = (SvgPosition(num("cx"), num("cy")), num("r"));
+                            if get("stroke").as_deref() == Some("none") {
+                                vec![Shape::Open(spiral(centre, radius, pen_width))]
+                            } else {
+                                vec![Shape::Circle{centre, radius}]
+                            }
+                        }
+                        "line" => {
+                            let mut path = PathBuilder::new();
+                            path.move_to(SvgPosition(num("x1"), num("y1")));
+                            path.line_to(SvgPosition(num("x2"), num("y2")));
+                            vec![Shape::Open(path)]
+                        }
+                        "path" => {
+                            let data = Data::parse(&get("d").unwrap_or_default()).map_err(Error::other)?;
+                            sub_paths(&data).into_iter().map(Shape::Open).collect()
+                        }
+                        _ => continue,
+                    };
+                    strokes.extend(shapes.into_iter().map(|shape| Stroke { shape, colour: colour.clone(), width }));
+                }
+                _ => (),
+            }
+        }
+        let view_box = attributes.iter()
+            .find(|(name, _)| name.as_str() == "viewBox")
+            .and_then(|(_, value)| parse_view_box(value))
+            .ok_or_else(|| Error::other("the svg needs a viewBox to plot"))?;
+        let mut colours: Vec<String> = Vec::new();
+        for stroke in &strokes {
+            if !colours.contains(&stroke.colour) {
+                colours.push(stroke.colour.clone());
+            }
+        }
+        let mut paths = Vec::new();
+        let (mut before, mut after) = (0.0, 0.0);
+        let mut pen = SvgPosition(0.0, 0.0);
+        for colour in colours {
+            let (same, rest): (Vec<Stroke>, Vec<Stroke>) = strokes.into_iter().partition(|stroke| stroke.colour == colour);
+            strokes = rest;
+            before += travel(&same, pen);
+            for (stroke, reversed) in nearest_first(same, pen) {
+                let (first, last) = stroke.ends(pen);
+                let (start, end) = if reversed {(last, first)} else {(first, last)};
+                after += dist(pen, start);
+                paths.push(stroke.into_pen_path(start, reversed));
+                pen = end;
+            }
+        }
+        Ok(Plot { view_box, attributes, labels, paths, travel: (before, after) })
+    }
+    pub fn view_box(&self) -> (f32,f32,f32,f32) {
+        self.view_box
+    }
+    pub fn paths(&self) -> &[PenPath] {
+        &self.paths
+    }
+    //in the order they're drawn, one pen each
+    pub fn colours(&self) -> Vec<&str> {
+        let mut colours: Vec<&str> = Vec::new();
+        for path in &self.paths {
+            if colours.last() != Some(&path.colour()) {
+                colours.push(path.colour());
+            }
+        }
+        colours
+    }
+    pub fn title(&self) -> Option<&str> {
+        self.labels.iter().find(|(tag, _, _)| tag == "title").map(|(_, _, text)| text.as_str())
+    }
+    //pen up travel (as drawn, after sorting), in svg units
+    pub fn travel(&self) -> (f32,f32) {
+        self.travel
+    }
+    //back to an svg, a group per pen
+    pub fn document(&self) -> Document {
+        let mut doc = Document::new();
+        for (name, value) in &self.attributes {
+            doc = doc.set(name.as_str(), value.clone());
+        }
+        for (tag, id, text) in &self.labels {
+            doc = match tag.as_str() {
+                "title" => doc.add(Title::new(text.as_str()).set("id", id.as_str())),
+                _ => doc.add(Description::new().set("id", id.as_str()).add(svg::node::Text::new(text.as_str()))),
+            };
+        }
+        for colour in self.colours() {
+            let mut group = Group::new()
+                .set("class", "pen")
+                .set("data-pen", colour)
+                .set("fill", "none")
+                .set("stroke-linecap", "round")
+                .set("stroke-linejoin", "round");
+            for path in self.paths.iter().filter(|path| path.colour == colour) {
+                group = group.add(Path::new()
+                    .set("d", path.path.clone().build_data())
+                    .set("stroke", colour)
+                    .set("stroke-width", path.width));
+            }
+            doc = doc.add(group);
+        }
+        doc
+    }
+    //every stroke as straight lines in mm with y going up, scaled to fit the machine's bed, paired with its pen number
+    pub fn machine_lines(&self, machine:&MachineOptions) -> Vec<(usize, Vec<(f32,f32)>)> {
+        let scale = machine.scale(self.view_box);
+        let colours = self.colours();
+        let mut result = Vec::new();
+        for path in &self.paths {
+            let pen = colours.iter().position(|colour| *colour == path.colour).unwrap_or(0);
+            for line in path.path.flatten(machine.tolerance/scale) {
+                let points = line.into_iter().map(|point| machine.to_bed(self.view_box, point)).collect();
+                result.push((pen, points));
+            }
+        }
+        result
+    }
+}
+
+impl Default for MachineOptions {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_BED.0,
+            height: DEFAULT_BED.1,
+            tolerance: DEFAULT_TOLERANCE,
+            feed_rate: DEFAULT_FEED_RATE,
+            travel_rate: DEFAULT_TRAVEL_RATE,
+            pen_up: DEFAULT_PEN_HEIGHTS.0,
+            pen_down: DEFAULT_PEN_HEIGHTS.1,
+        }
+    }
+}
+
+impl MachineOptions {
+    //the drawing is scaled to fit inside width by height, from the bottom left corner
+    pub fn new(width:f32, height:f32) -> MachineOptions {
+        MachineOptions { width, height, ..Default::default() }
+    }
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+    pub fn set_bed(&mut self, width:f32, height:f32) {
+        self.width = width;
+        self.height = height;
+    }
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+    pub fn feed_rate(&self) -> f32 {
+        self.feed_rate
+    }
+    pub fn travel_rate(&self) -> f32 {
+        self.travel_rate
+    }
+    pub fn pen_up(&self) -> f32 {
+        self.pen_up
+    }
+    pub fn pen_down(&self) -> f32 {
+        self.pen_down
+    }
+    //furthest a straight segment strays from a curve it stands in for
+    //has to be above 0, no number of straight lines follows a curve exactly
+    pub fn set_tolerance(&mut self, tolerance:f32) -> Result<(), Error> {
+        if tolerance.is_nan() || tolerance <= 0.0 {
+            return Err(Error::other(format!("tolerance has to be more than 0, not {}", tolerance)))
+        }
+        self.tolerance = tolerance;
+        Ok(())
+    }
+    //speed with the pen down
+    pub fn set_feed_rate(&mut self, feed_rate:f32) {
+        self.feed_rate = feed_rate;
+    }
+    //speed with the pen up
+    pub fn set_travel_rate(&mut self, travel_rate:f32) {
+        self.travel_rate = travel_rate;
+    }
+    //z heights, for machines that lift the pen or tool with z
+    pub fn set_pen_heights(&mut self, up:f32, down:f32) {
+        self.pen_up = up;
+        self.pen_down = down;
+    }
+    //mm per svg unit, with the view box fitted to the bed
+    pub fn scale(&self, view_box:(f32,f32,f32,f32)) -> f32 {
+        (self.width/view_box.2).min(self.height/view_box.3)
+    }
+    //mm from the bed's bottom left corner, y going up
+    pub fn to_bed(&self, view_box:(f32,f32,f32,f32), point:SvgPosition) -> (f32,f32) {
+        let (x, y, _, height) = view_box;
+        let scale = self.scale(view_box);
+        (((point.0 - x)*scale).clamp(0.0, self.width), ((y + height - point.1)*scale).clamp(0.0, self.height))
+    }
+}
+
+//reorders a drawing for a pen plotter, see Plot::new
+pub fn plot_order(doc:&Document, pen_width:f32) -> Result<Document, Error> {
+    Ok(Plot::new(doc, pen_width)?.document())
+}
+
+pub(crate) fn parse_view_box(value:&str) -> Option<(f32,f32,f32,f32)> {
+    let nums: Vec<f32> = value.split([' ', ',']).filter(|num| !num.is_empty()).map(|num| num.parse().ok()).collect::<Option<_>>()?;
+    match nums[..] {
+        [x, y, width, height] if width > 0.0 && height > 0.0 => Some((x, y, width, height)),
+        _ => None,
+    }
+}
+
+//greedy, always the stroke with the closest end next, reversing open ones that are nearer backwards
+fn nearest_first(mut strokes:Vec<Stroke>, mut pen:SvgPosition) -> Vec<(Stroke, bool)> {
+    let mut result = Vec::with_capacity(strokes.len());
+    while !strokes.is_empty() {
+        let (index, reversed, _) = strokes.iter().enumerate()
+            .flat_map(|(i, stroke)| {
+                let (start, end) = stroke.ends(pen);
+                let backwards = (!stroke.closed()).then(|| (i, true, dist(pen, end)));
+                std::iter::once((i, false, dist(pen, start))).chain(backwards)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .expect("strokes isn't empty");
+        let stroke = strokes.swap_remove(index);
+        let (start, end) = stroke.ends(pen);
+        pen = if reversed {start} else {end};
+        result.push((stroke, reversed));
+    }
+    result
+}
+
+//how far the pen moves lifted, drawing in the order given
+fn travel(strokes:&[Stroke], mut pen:SvgPosition) -> f32 {
+    let mut total = 0.0;
+    for stroke in strokes {
+        let (start, end) = stroke.ends(pen);
+        total += dist(pen, start);
+        pen = end;
+    }
+    total
+}
+
+//splits path data at each move. only the commands this crate writes are understood, m l a & z
+pub(crate) fn sub_paths(data:&Data) -> Vec<PathBuilder> {
+    let mut result: Vec<PathBuilder> = Vec::new();
+    let mut at = SvgPosition(0.0, 0.0);
+    let mut start = at;
+    for command in data.iter() {
+        let to = |params:&[f32], position:&Position| match position {
+            Position::Absolute => SvgPosition(params[0], params[1]),
+            Position::Relative => SvgPosition(at.0 + params[0], at.1 + params[1]),
+        };
+        match command {
+            Command::Move(position, params) => {
+                at = to(params, position);
+                start = at;
+                let mut path = PathBuilder::new();
+                path.move_to(at);
+                result.push(path);
+            }
+            Command::Line(position, params) => {
+                at = to(params, position);
+                result.last_mut().into_iter().for_each(|path| path.line_to(at));
+            }
+            Command::EllipticalArc(position, params) => {
+                at = to(&params[5..], position);
+                let (large, sweep) = (LargeArcFlag(params[3] != 0.0), SweepDirection(params[4] != 0.0));
+                result.last_mut().into_iter().for_each(|path| path.arc_to(at, params[0], large, sweep));
+            }
+            Command::Close => {
+                if dist(at, start) >= JOIN_TOLERANCE {
+                    result.last_mut().into_iter().for_each(|path| path.line_to(start));
+                }
+                at = start;
+            }
+            _ => (),
+        }
+    }
+    result.retain(|path| path.len() > 1);
+    result
+}
+
+//fills a dot in half turns, each a pen width further in than the last full turn
+fn spiral(centre:SvgPosition, radius:f32, pen_width:f32) -> PathBuilder {
+    let mut path = PathBuilder::new();
+    let outer = radius - pen_width/2.0;
+    if outer <= 0.0 {
+        //too small to go round, so just a touch of the pen
+        path.move_to(centre);
+        path.line_to(centre);
+        return path
+    }
+    let step = pen_width/2.0;
+    let mut reach = outer;
+    let mut side = 1.0;
+    path.move_to(SvgPosition(centre.0 + reach, centre.1));
+    while reach > 0.0 {
+        let next = (reach - step).max(0.0);
+        path.arc_to(SvgPosition(centre.0 - side*next, centre.1), (reach + next)/2.0, LargeArcFlag(false), SweepDirection(true));
+        reach = next;
+        side = -side;
+    }
+    path
+}
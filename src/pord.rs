@@ -2,6 +2,8 @@ use std::cell::Cell;
 use std::rc::{Rc, Weak};
 use std::f32::consts::PI;
 
+use crate::error::GallifreyanError;
+
 #[derive(Debug, Clone)]
 pub enum PordOrCord{
     Pord(POrd),
@@ -9,10 +11,13 @@ pub enum PordOrCord{
     Gord(f32,f32) 
 }
 //Always use svg for POrds
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct POrd {
     r: Cell<f32>,
     theta: Cell<f32>,
+    //turn & resize everything anchored here, not the pord itself
+    rotation: Cell<f32>,
+    scale: Cell<f32>,
     anchor: Weak<PordOrCord>,
 }
 
@@ -23,23 +28,29 @@ pub trait Cartesian {
         (x, -y)
     }
     fn abs_svg_xy(&self, svg_origin:(f32,f32)) -> (f32,f32);
+    //(rotation, scale) that things anchored here are placed with, built up through every anchor above
+    fn frame(&self) -> (f32,f32) {
+        (0.0, 1.0)
+    }
+    //an svg offset from here, as the (r, theta) a pord anchored here needs to land on it
+    fn local_polar(&self, svg_offset:(f32,f32)) -> (f32,f32) {
+        let (rotation, scale) = self.frame();
+        (svg_offset.0.hypot(svg_offset.1)/scale, svg_offset.0.atan2(svg_offset.1) - rotation)
+    }
     fn svg_xy_to(&self, other:impl Cartesian) -> (f32,f32) {
         let svg_origin = (0.0,0.0); 
         let (self_x,self_y) = self.abs_svg_xy(svg_origin);
         let (other_x,other_y) = other.abs_svg_xy(svg_origin);
         (other_x - self_x, other_y - self_y)
     }
+    //in our own frame, so it's the theta a pord anchored here would need
     fn angle_to(&self, other:&impl Cartesian) -> f32 {
         //we don't actually care about the final translation
         let svg_origin = (0.0,0.0); 
         let (x1,y1) = self.abs_svg_xy(svg_origin);
         let (x2,y2) = other.abs_svg_xy(svg_origin);
-        let raw = (x2-x1).atan2(y2-y1);
-        if raw < 0.0 {
-            raw + 2.0*PI
-        } else {
-            raw
-        }
+        let raw = (x2-x1).atan2(y2-y1) - self.frame().0;
+        raw.rem_euclid(2.0*PI)
     }
     fn dist_to_sq(&self, other:&impl Cartesian) -> f32 {
         let svg_origin = (0.0,0.0);
@@ -60,6 +71,9 @@ pub trait Polar {
             PordOrCord::Gord(x, y) => (svg_origin.0 + x, svg_origin.1 - y),
         })
     }
+    fn anchor_frame(&self) -> (f32,f32) {
+        self.anchor().upgrade().map_or((0.0, 1.0), |poc| poc.frame())
+    }
 }
 
 impl Cartesian for PordOrCord {
@@ -77,24 +91,31 @@ impl Cartesian for PordOrCord {
             PordOrCord::Pord(poi) => poi.abs_svg_xy(svg_origin)
         }
     }
+    fn frame(&self) -> (f32,f32) {
+        match &self {
+            PordOrCord::Pord(pord) => pord.frame(),
+            _ => (0.0, 1.0),
+        }
+    }
 }
 
 impl Cartesian for POrd {
     fn rel_xy(&self) -> (f32,f32) {
-        let (a,b) = self.theta().sin_cos();
-        (self.r() * a, -self.r() * b)
+        let (rotation, scale) = self.anchor_frame();
+        let (a,b) = (self.theta() + rotation).sin_cos();
+        let r = scale * self.r();
+        (r * a, -r * b)
     }
     fn abs_svg_xy(&self, svg_origin:(f32,f32)) -> (f32,f32) {
-        let (x,y) = match self.anchor_abs_svg_xy(svg_origin) {
-            Some(anchor_xy) => anchor_xy,
-            None => {
-                println!("Anchor missing. Using svg origin instead.");
-                svg_origin
-            },
-        };
+        //a dropped anchor falls back to the svg origin, words & sentences name it before drawing
+        let (x,y) = self.anchor_abs_svg_xy(svg_origin).unwrap_or(svg_origin);
         let (x_rel, y_rel) = self.rel_svg_xy();
         (x+x_rel,y+y_rel)
     } 
+    fn frame(&self) -> (f32,f32) {
+        let (rotation, scale) = self.anchor_frame();
+        (rotation + self.rotation(), scale * self.scale())
+    }
 }
 
 impl POrd {
@@ -102,7 +123,7 @@ impl POrd {
         let r = Cell::new(radius);
         let angle = Cell::new(theta);
         let anchor = Rc::downgrade(&anchor);
-        POrd{r, theta:angle, anchor}
+        POrd{r, theta:angle, rotation:Cell::new(0.0), scale:Cell::new(1.0), anchor}
     }
     pub fn add_dist(&mut self, added_dist:f32) {
         let dist = self.r.get_mut();
@@ -112,6 +133,32 @@ impl POrd {
         let theta = self.theta.get_mut();
         *theta = new_theta;
     }
+    pub fn rotation(&self) -> f32 {
+        self.rotation.get()
+    }
+    //spins everything anchored here (& further down) round this pord
+    pub fn set_rotation(&self, rotation:f32) {
+        self.rotation.set(rotation);
+    }
+    pub fn scale(&self) -> f32 {
+        self.scale.get()
+    }
+    //grows everything anchored here from this pord, strokes keep their width
+    pub fn set_scale(&self, scale:f32) {
+        self.scale.set(scale);
+    }
+}
+
+impl Default for POrd {
+    fn default() -> Self {
+        POrd{
+            r: Cell::new(0.0),
+            theta: Cell::new(0.0),
+            rotation: Cell::new(0.0),
+            scale: Cell::new(1.0),
+            anchor: Weak::new(),
+        }
+    }
 }
 
 impl From<POrd> for PordOrCord {
@@ -144,6 +191,23 @@ impl PordOrCord {
             _ => None
         }
     }
+    //cords don't have a frame of their own, only pords can be turned or scaled
+    pub fn as_pord(&self) -> Option<&POrd> {
+        match self {
+            PordOrCord::Pord(pord) => Some(pord),
+            _ => None
+        }
+    }
+    //follows the anchors up to a cord, where every chain ends. anchors are fixed from an Rc that already exists,
+    //so they can't loop. owner names whatever this pord belongs to, for the error
+    pub fn check_anchors(&self, owner:&str) -> Result<(), GallifreyanError> {
+        let mut anchor = self.get_anchor();
+        while let Some(weak) = anchor {
+            let poc = weak.upgrade().ok_or_else(|| GallifreyanError::DroppedAnchor(owner.to_string()))?;
+            anchor = poc.get_anchor();
+        }
+        Ok(())
+    }
     pub fn get_anchor(&self) -> Option<Weak<PordOrCord>> {
         match self {
             PordOrCord::Pord(pord) => {
@@ -0,0 +1,68 @@
+use std::io::Error;
+
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{Options, Tree};
+use svg::Document;
+
+use crate::canvas;
+
+//svg user units are css pixels
+const SVG_DPI :f32 = 96.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PngSize {
+    Scale(f32),
+    Dpi(f32),
+    //output width in pixels, height follows the aspect ratio
+    Width(u32),
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PngOptions {
+    size:PngSize,
+    transparent:bool,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self { size: PngSize::Scale(1.0), transparent: false }
+    }
+}
+
+impl PngOptions {
+    pub fn new(size:PngSize) -> PngOptions {
+        PngOptions { size, transparent: false }
+    }
+    //leaves out the canvas_init background so the png can be composited
+    pub fn set_transparent(&mut self, transparent:bool) {
+        self.transparent = transparent;
+    }
+    pub fn size(&self) -> PngSize {
+        self.size
+    }
+    pub fn transparent(&self) -> bool {
+        self.transparent
+    }
+    fn scale(&self, tree:&Tree) -> f32 {
+        match self.size {
+            PngSize::Scale(scale) => scale,
+            PngSize::Dpi(dpi) => dpi/SVG_DPI,
+            PngSize::Width(width) => width as f32/tree.size().width(),
+        }
+    }
+}
+
+pub fn render_png_bytes(doc:&Document, options:&PngOptions) -> Result<Vec<u8>, Error> {
+    let content = match options.transparent {
+        true => canvas::without_background(doc)?.to_string(),
+        false => doc.to_string(),
+    };
+    let tree = Tree::from_str(&content, &Options::default()).map_err(Error::other)?;
+    let scale = options.scale(&tree);
+    let size = tree.size();
+    let (width, height) = ((size.width()*scale).ceil() as u32, (size.height()*scale).ceil() as u32);
+    let mut pixmap = Pixmap::new(width, height)
+        .ok_or_else(|| Error::other(format!("can't make a {}x{} image", width, height)))?;
+    let transform = Transform::from_scale(scale, scale);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(Error::other)
+}
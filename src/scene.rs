@@ -0,0 +1,392 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use svg::Document;
+use svg::node::element::Group;
+
+use crate::basic;
+use crate::canvas::{Background, Canvas};
+use crate::ctx::{ColourContext, Context, StrokeContext};
+use crate::decorator::{CirculcarLine, Linebuilder, StraightLine};
+use crate::error::GallifreyanError;
+use crate::plotter;
+use crate::pord::{Cartesian, POrd, PordOrCord};
+use crate::translate::VowelKind;
+use crate::word::{Word, WordArc, WordCircle};
+use crate::{Decoration, StemType};
+
+//reserved id for the centre of the canvas
+const ORIGIN_ID :&str = "origin";
+//reserved id for the context used when none is given
+const DEFAULT_CTX_ID :&str = "default";
+const DEFAULT_STROKE :f32 = 10.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    pub canvas:CanvasDef,
+    #[serde(default)]
+    pub contexts:Vec<ContextDef>,
+    #[serde(default)]
+    pub points:Vec<PointDef>,
+    #[serde(default)]
+    pub words:Vec<WordDef>,
+    #[serde(default)]
+    pub circles:Vec<CircleDef>,
+    #[serde(default)]
+    pub dots:Vec<CircleDef>,
+    #[serde(default)]
+    pub lines:Vec<LineDef>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CanvasDef {
+    pub width:u64,
+    pub height:u64,
+    //a colour or "transparent"
+    #[serde(default = "white")]
+    pub background:String,
+    #[serde(default)]
+    pub margin:f32,
+    //for screen readers, see canvas::Canvas::set_title
+    pub title:Option<String>,
+    pub desc:Option<String>,
+    //single strokes in pen order, see plotter::plot_order. pen_width defaults to the thinnest context stroke
+    #[serde(default)]
+    pub plot:bool,
+    pub pen_width:Option<f32>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContextDef {
+    pub id:String,
+    #[serde(default = "white")]
+    pub bg:String,
+    #[serde(default = "none")]
+    pub fill:String,
+    #[serde(default = "black")]
+    pub stroke:String,
+    pub stroke_width:f32,
+    pub inner_stroke:Option<f32>,
+    pub outer_stroke:Option<f32>,
+}
+//polar position relative to another element, angles are in radians.
+//rotation & scale turn and resize everything anchored on this one, not the element itself
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnchorDef {
+    pub to:Option<String>,
+    #[serde(default)]
+    pub r:f32,
+    #[serde(default)]
+    pub theta:f32,
+    #[serde(default)]
+    pub rotation:f32,
+    pub scale:Option<f32>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PointDef {
+    pub id:String,
+    pub at:AnchorDef,
+}
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordKind {
+    #[default]
+    Circle,
+    Arc,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WordDef {
+    pub id:String,
+    #[serde(default)]
+    pub kind:WordKind,
+    pub at:AnchorDef,
+    pub radius:f32,
+    pub ctx:Option<String>,
+    pub start_angle:Option<f32>,
+    pub end_angle:Option<f32>,
+    #[serde(default)]
+    pub tip_length:f32,
+    #[serde(default)]
+    pub letters:Vec<LetterDef>,
+    #[serde(default)]
+    pub vowels:Vec<VowelDef>,
+}
+//letters without an id can still be referenced as "<word id>.<index>"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LetterDef {
+    pub id:Option<String>,
+    #[serde(default)]
+    pub at:AnchorDef,
+    pub radius:f32,
+    pub stem:StemType,
+    pub ctx:Option<String>,
+    #[serde(default)]
+    pub decoration:Decoration,
+    pub vowel:Option<VowelDef>,
+    //what the letter stands for, kept in the svg as data-letter
+    pub text:Option<String>,
+}
+//theta is only used for vowels that aren't on a letter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VowelDef {
+    pub kind:VowelKind,
+    pub radius:f32,
+    #[serde(default)]
+    pub theta:f32,
+    pub ctx:Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircleDef {
+    pub at:AnchorDef,
+    pub radius:f32,
+    pub ctx:Option<String>,
+}
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineKind {
+    #[default]
+    Straight,
+    Small,
+    Big,
+}
+//curved lines take a third point as the centre of their circle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LineDef {
+    #[serde(default)]
+    pub kind:LineKind,
+    pub points:Vec<AnchorDef>,
+    pub ctx:Option<String>,
+}
+
+//turns ids into pords, on demand so they can be declared in any order
+struct Resolver {
+    defs:HashMap<String, AnchorDef>,
+    done:HashMap<String, Rc<PordOrCord>>,
+    visiting:HashSet<String>,
+}
+
+fn white() -> String {"white".to_string()}
+fn none() -> String {"none".to_string()}
+fn black() -> String {"black".to_string()}
+
+fn scene_err(msg:impl ToString) -> GallifreyanError {
+    GallifreyanError::InvalidScene(msg.to_string())
+}
+
+impl Scene {
+    pub fn from_toml_str(text:&str) -> Result<Scene, GallifreyanError> {
+        toml::from_str(text).map_err(scene_err)
+    }
+    pub fn from_json_str(text:&str) -> Result<Scene, GallifreyanError> {
+        serde_json::from_str(text).map_err(scene_err)
+    }
+    //json if the extension says so, toml otherwise
+    pub fn load(path:&Path) -> Result<Scene, GallifreyanError> {
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Scene::from_json_str(&text),
+            _ => Scene::from_toml_str(&text),
+        }
+    }
+    pub fn canvas(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.canvas.width as f32, self.canvas.height as f32);
+        canvas.set_margin(self.canvas.margin);
+        if let Some(title) = &self.canvas.title {
+            canvas.set_title(title);
+        }
+        if let Some(desc) = &self.canvas.desc {
+            canvas.set_desc(desc);
+        }
+        canvas.set_background(match self.canvas.background.as_str() {
+            "transparent" => Background::Transparent,
+            colour => Background::solid(colour),
+        });
+        canvas
+    }
+    //what plot mode spaces dot fills by, the canvas's pen_width or else the thinnest context stroke
+    pub fn pen_width(&self) -> f32 {
+        self.canvas.pen_width.unwrap_or_else(|| {
+            self.contexts.iter().map(|def| def.stroke_width).reduce(f32::min).unwrap_or(DEFAULT_STROKE)
+        })
+    }
+    pub fn build(&self) -> Result<Document, GallifreyanError> {
+        let (mut doc, svg_origin) = self.canvas().build();
+        let origin = PordOrCord::gal_origin(svg_origin);
+        let contexts = self.contexts(&origin);
+        let ctx = |id:&Option<String>| -> Result<Context, GallifreyanError> {
+            let id = id.as_deref().unwrap_or(DEFAULT_CTX_ID);
+            contexts.get(id).cloned().ok_or_else(|| scene_err(format!("no context called '{}'", id)))
+        };
+        let mut resolver = self.resolver(&origin, &ctx)?;
+        for word_def in &self.words {
+            let word_pord = resolver.resolve(&word_def.id)?;
+            let word_ctx = ctx(&word_def.ctx)?;
+            doc = match word_def.kind {
+                WordKind::Circle => {
+                    let mut word = WordCircle::new(&word_def.id, word_pord, word_def.radius, word_ctx);
+                    word.set_id(&word_def.id);
+                    self.add_letters(&mut word, word_def, &mut resolver, &ctx)?;
+                    word.try_draw(doc)?
+                }
+                WordKind::Arc => {
+                    let (Some(start), Some(end)) = (word_def.start_angle, word_def.end_angle) else {
+                        return Err(scene_err(format!("arc '{}' needs a start_angle and end_angle", word_def.id)))
+                    };
+                    let mut word = WordArc::new(&word_def.id, word_pord, word_def.radius, start, end, word_def.tip_length, word_ctx);
+                    word.set_id(&word_def.id);
+                    self.add_letters(&mut word, word_def, &mut resolver, &ctx)?;
+                    word.try_draw(doc)?
+                }
+            };
+        }
+        let shapes = doc.get_children().len();
+        for circle in &self.circles {
+            let centre = resolver.place(&circle.at)?;
+            doc = basic::circle(doc, centre.as_ref(), circle.radius*centre.frame().1, &ctx(&circle.ctx)?);
+        }
+        for dot in &self.dots {
+            let centre = resolver.place(&dot.at)?;
+            doc = basic::circle(doc, centre.as_ref(), dot.radius*centre.frame().1, &ctx(&dot.ctx)?.new_filled());
+        }
+        for line in &self.lines {
+            let mut builder = Linebuilder::new(&ctx(&line.ctx)?);
+            for point in &line.points {
+                let pord = resolver.place(point)?;
+                if builder.add_pord(pord).is_err() {
+                    return Err(scene_err("lines take at most three points"))
+                }
+            }
+            doc = match line.kind {
+                LineKind::Straight => {
+                    let straight: StraightLine = builder.try_into().map_err(|_| scene_err("straight lines need two points"))?;
+                    straight.draw(doc)
+                }
+                LineKind::Small | LineKind::Big => {
+                    let curved: CirculcarLine = builder.try_into().map_err(|_| scene_err("curved lines need three points"))?;
+                    if line.kind == LineKind::Small {curved.draw_small(doc)} else {curved.draw_big(doc)}
+                }
+            };
+        }
+        if doc.get_children().len() > shapes {
+            doc = basic::group_since(doc, shapes, Group::new().set("id", "shapes").set("class", "shapes"));
+        }
+        if self.canvas.plot {
+            doc = plotter::plot_order(&doc, self.pen_width())?;
+        }
+        Ok(doc)
+    }
+    fn contexts(&self, origin:&Rc<PordOrCord>) -> HashMap<String, Context> {
+        let mut result = HashMap::new();
+        result.insert(DEFAULT_CTX_ID.to_string(), Context::new(ColourContext::default(), StrokeContext::new(DEFAULT_STROKE), origin.clone()));
+        for def in &self.contexts {
+            let mut stroke = StrokeContext::new(def.stroke_width);
+            if let Some(i_stroke) = def.inner_stroke {
+                stroke.set_i_stroke(i_stroke);
+            }
+            if let Some(o_stroke) = def.outer_stroke {
+                stroke.set_o_stroke(o_stroke);
+            }
+            let colour = ColourContext::new(&def.bg, &def.fill, &def.stroke);
+            result.insert(def.id.clone(), Context::new(colour, stroke, origin.clone()));
+        }
+        for ctx in result.values_mut() {
+            ctx.set_centre_lines(self.canvas.plot);
+        }
+        result
+    }
+    fn resolver(&self, origin:&Rc<PordOrCord>, ctx:&impl Fn(&Option<String>) -> Result<Context, GallifreyanError>) -> Result<Resolver, GallifreyanError> {
+        let mut resolver = Resolver {
+            defs: HashMap::new(),
+            done: HashMap::from([(ORIGIN_ID.to_string(), origin.clone())]),
+            visiting: HashSet::new(),
+        };
+        for point in &self.points {
+            resolver.define(&point.id, point.at.clone())?;
+        }
+        for word in &self.words {
+            resolver.define(&word.id, word.at.clone())?;
+            let stroke = ctx(&word.ctx)?.stroke().strokewidth();
+            for (i, letter) in word.letters.iter().enumerate() {
+                let mut at = letter.at.clone();
+                //same as Word::new_letter_from_data, s letters sit on the middle of the line
+                if at.to.is_none() && letter.stem == StemType::S {
+                    at.r += stroke/2.0;
+                }
+                at.to.get_or_insert(word.id.clone());
+                resolver.define(&letter_key(word, i), at)?;
+            }
+        }
+        Ok(resolver)
+    }
+    fn add_letters<W:Word>(&self, word:&mut W, def:&WordDef, resolver:&mut Resolver, ctx:&impl Fn(&Option<String>) -> Result<Context, GallifreyanError>) -> Result<(), GallifreyanError> {
+        for (i, letter) in def.letters.iter().enumerate() {
+            let pord = resolver.resolve(&letter_key(def, i))?;
+            word.try_new_letter(pord.clone(), letter.radius, letter.stem, optional_ctx(&letter.ctx, ctx)?)?;
+            word.decorate(&pord, letter.decoration)?;
+            if let Some(text) = &letter.text {
+                word.set_letter_text(&pord, text)?;
+            }
+            if let Some(vowel) = &letter.vowel {
+                word.attach_vowel(&pord, vowel.kind, vowel.radius, optional_ctx(&vowel.ctx, ctx)?)?;
+            }
+        }
+        for vowel in &def.vowels {
+            word.new_vowel(vowel.kind, vowel.theta, vowel.radius, optional_ctx(&vowel.ctx, ctx)?);
+        }
+        Ok(())
+    }
+}
+
+//letters & vowels fall back on their word's context
+fn optional_ctx(id:&Option<String>, ctx:&impl Fn(&Option<String>) -> Result<Context, GallifreyanError>) -> Result<Option<Context>, GallifreyanError> {
+    match id {
+        Some(_) => ctx(id).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn letter_key(word:&WordDef, index:usize) -> String {
+    match &word.letters[index].id {
+        Some(id) => id.clone(),
+        None => format!("{}.{}", word.id, index),
+    }
+}
+
+impl Resolver {
+    fn define(&mut self, id:&str, at:AnchorDef) -> Result<(), GallifreyanError> {
+        if id == ORIGIN_ID || self.defs.insert(id.to_string(), at).is_some() {
+            return Err(scene_err(format!("'{}' is used as an id more than once", id)))
+        }
+        Ok(())
+    }
+    fn resolve(&mut self, id:&str) -> Result<Rc<PordOrCord>, GallifreyanError> {
+        if let Some(done) = self.done.get(id) {
+            return Ok(done.clone())
+        }
+        let at = self.defs.get(id).cloned().ok_or_else(|| scene_err(format!("nothing called '{}'", id)))?;
+        if !self.visiting.insert(id.to_string()) {
+            return Err(scene_err(format!("the anchors around '{}' go round in a loop", id)))
+        }
+        let pord = self.place(&at)?;
+        self.visiting.remove(id);
+        self.done.insert(id.to_string(), pord.clone());
+        Ok(pord)
+    }
+    fn place(&mut self, at:&AnchorDef) -> Result<Rc<PordOrCord>, GallifreyanError> {
+        let anchor = self.resolve(at.to.as_deref().unwrap_or(ORIGIN_ID))?;
+        let pord = POrd::new(at.r, at.theta, anchor);
+        pord.set_rotation(at.rotation);
+        pord.set_scale(at.scale.unwrap_or(1.0));
+        Ok(Rc::new(PordOrCord::Pord(pord)))
+    }
+}
@@ -0,0 +1,515 @@
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+use svg::Document;
+use svg::node::element::Group;
+
+use crate::basic;
+use crate::bounds::{BBox, Bounded};
+use crate::ctx::Context;
+use crate::error::{self, GallifreyanError};
+use crate::geometry;
+use crate::Decoration;
+use crate::decorator::{self, CirculcarLine, Linebuilder, StraightLine};
+use crate::layout::{self, Placement, WordLayout};
+use crate::pord::{Cartesian, POrd, PordOrCord};
+use crate::translate;
+use crate::utils;
+use crate::word::{Word, WordCircle};
+
+const RING_GAP_RATIO :f32 = 0.03;
+const WORD_GAP_RATIO :f32 = 0.03;
+//room for the vowels that hang outside a word circle
+const WORD_FOOTPRINT :f32 = 1.2;
+//how far a letter line may turn to meet another, as a cosine
+const LINE_MATCH_COS :f32 = 0.5;
+//points checked along a joining line when looking for letters in the way
+const LINE_SAMPLES :usize = 32;
+//how far curved joins bow out, as a fraction of the distance they cover
+const LINE_BENDS :[f32; 2] = [1.0, 0.25];
+//ring points tried either side of a blocked line, up to a quarter turn away
+const RING_SEARCH_STEPS :usize = 12;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Punctuation {Period, Comma, Semicolon, Colon, Question, Exclamation, Quote, Hyphen}
+//a letter line waiting to be joined, & the word it came from
+struct Stub {
+    line:StraightLine,
+    word:usize,
+    letter:Option<Rc<PordOrCord>>,
+    //the svg ids of the letter & of the line once it's drawn
+    letter_id:String,
+    id:String,
+}
+//a word circle, letter, vowel or dot that joining lines should go around.
+//holding the pord also keeps them anchored after their words have been drawn & dropped
+struct Obstacle {
+    pord:Rc<PordOrCord>,
+    radius:f32,
+    owner:Owner,
+}
+//what a line has to start from to be let through an obstacle
+enum Owner {
+    Word(usize),
+    //the letter itself, its dots or its vowel
+    Letter(Rc<PordOrCord>),
+    //vowels on their own are in the way of everything
+    Nothing,
+}
+#[derive(Debug, Clone)]
+pub struct SentenceCircle<W:Word> {
+    name:String,
+    id:Option<String>,
+    pord:Rc<PordOrCord>,
+    radius:f32,
+    words:Vec<W>,
+    punctuation:Vec<(usize,Punctuation)>,
+    default_ctx:Context,
+    ring_gap:f32,
+}
+
+impl Punctuation {
+    pub fn from_char(c:char) -> Option<Punctuation> {
+        Some(match c {
+            '.' => Punctuation::Period,
+            ',' => Punctuation::Comma,
+            ';' => Punctuation::Semicolon,
+            ':' => Punctuation::Colon,
+            '?' => Punctuation::Question,
+            '!' => Punctuation::Exclamation,
+            '"' | '\'' => Punctuation::Quote,
+            '-' => Punctuation::Hyphen,
+            _ => return None
+        })
+    }
+}
+
+impl<W:Word> SentenceCircle<W> {
+    pub fn new(name:&str, pord:Rc<PordOrCord>, radius:f32, ctx:Context) -> SentenceCircle<W> {
+        SentenceCircle {
+            name: name.to_string(),
+            id: None,
+            pord,
+            radius,
+            words: Vec::new(),
+            punctuation: Vec::new(),
+            default_ctx: ctx,
+            ring_gap: RING_GAP_RATIO * radius,
+        }
+    }
+    //the svg id of the sentence's group, made from the text unless it's been set
+    pub fn id(&self) -> String {
+        self.id.clone().unwrap_or_else(|| {
+            let slug = utils::id_slug(&self.name);
+            if slug.is_empty() {"sentence".to_string()} else {format!("{}-sentence", slug)}
+        })
+    }
+    pub fn set_id(&mut self, id:&str) {
+        self.id = Some(id.to_string());
+    }
+    pub fn pord(&self) -> Rc<PordOrCord> {
+        self.pord.clone()
+    }
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+    pub fn inner_radius(&self) -> f32 {
+        self.radius - self.ring_gap
+    }
+    pub fn set_ring_gap(&mut self, ring_gap:f32) {
+        self.ring_gap = ring_gap;
+    }
+    pub fn words(&self) -> &[W] {
+        &self.words
+    }
+    pub fn push_word(&mut self, word:W) {
+        self.words.push(word);
+    }
+    pub fn add_punctuation(&mut self, after_word:usize, mark:Punctuation) {
+        self.punctuation.push((after_word, mark));
+    }
+    //where to put each word & how big to make it, sized by letter count
+    //fails if they still overlap after every try
+    pub fn word_slots(&self, letter_counts:&[usize]) -> Result<Vec<(Rc<PordOrCord>, f32)>, GallifreyanError> {
+        let inner = self.inner_radius();
+        let gap = WORD_GAP_RATIO * inner;
+        if letter_counts.len() == 1 {
+            let centre = Rc::new(PordOrCord::Pord(POrd::new(0.0, 0.0, self.pord.clone())));
+            return Ok(vec![(centre, (inner - gap)/WORD_FOOTPRINT)])
+        }
+        let weights: Vec<f32> = letter_counts.iter().map(|count| (*count).max(1) as f32).collect();
+        let angles = layout::weighted_angles(&weights, 0.0);
+        let max_weight = weights.iter().cloned().fold(0.0, f32::max);
+        let mut unit = (inner - gap)/(2.0*WORD_FOOTPRINT*max_weight);
+        for _ in 0..layout::MAX_FIT_ATTEMPTS {
+            let placed: Vec<Placement> = weights.iter().zip(&angles).map(|(weight, theta)| {
+                let footprint = weight * unit * WORD_FOOTPRINT;
+                Placement { dist: inner - footprint - gap, theta: *theta, radius: footprint }
+            }).collect();
+            if layout::ring_fits(&placed, gap) {
+                return Ok(placed.into_iter().map(|place| {
+                    let word_pord = Rc::new(PordOrCord::Pord(POrd::new(place.dist, place.theta, self.pord.clone())));
+                    (word_pord, place.radius/WORD_FOOTPRINT)
+                }).collect())
+            }
+            unit *= layout::SHRINK_STEP;
+        }
+        Err(GallifreyanError::GeometryUnsolvable("words don't fit the sentence ring".to_string()))
+    }
+    //the sentence's own, then every word's
+    pub fn anchor_faults(&self) -> Vec<GallifreyanError> {
+        self.pord.check_anchors(&self.name).err().into_iter()
+            .chain(self.words.iter().flat_map(|word| word.anchor_faults()))
+            .collect()
+    }
+    pub fn draw(self, doc:Document) -> Document {
+        self.draw_with(doc, |word, doc| Ok(word.draw(doc))).expect("plain draw doesn't fail")
+    }
+    pub fn try_draw(self, doc:Document) -> Result<Document, GallifreyanError> {
+        self.default_ctx.try_origin()?;
+        error::all_anchors(self.anchor_faults())?;
+        self.draw_with(doc, |word, doc| word.try_draw(doc))
+    }
+    fn draw_with(mut self, mut doc:Document, draw_word:impl Fn(W, Document) -> Result<Document, GallifreyanError>) -> Result<Document, GallifreyanError> {
+        println!("drawing {}...",self.name);
+        if let Err(fault) = self.pord.check_anchors(&self.name) {
+            println!("{}. Using svg origin instead.", fault);
+        }
+        let id = self.id();
+        let lines_id = format!("{}-lines", id);
+        let mut taken = basic::used_ids(&doc);
+        taken.extend([id.clone(), lines_id.clone()]);
+        let start = doc.get_children().len();
+        for (after, mark) in &self.punctuation {
+            doc = self.draw_mark(doc, *mark, self.mark_angle(*after));
+        }
+        let scale = self.pord.frame().1;
+        doc = basic::circle(doc, self.pord.as_ref(), self.radius*scale, &self.default_ctx);
+        doc = basic::circle(doc, self.pord.as_ref(), self.inner_radius()*scale, &self.default_ctx);
+        let mut words = std::mem::take(&mut self.words);
+        unique_word_ids(&mut words, taken);
+        let stubs = self.collect_stubs(&mut words);
+        let obstacles = self.obstacles(&mut words);
+        for word in words {
+            doc = draw_word(word, doc)?;
+        }
+        let lines = doc.get_children().len();
+        doc = self.draw_lines(doc, stubs, &obstacles);
+        doc = basic::group_since(doc, lines, Group::new().set("id", lines_id).set("class", "lines"));
+        Ok(basic::group_since(doc, start, Group::new().set("id", id).set("class", "sentence").set("role", "group").set("aria-label", self.name.as_str())))
+    }
+    fn collect_stubs(&self, words:&mut [W]) -> Vec<Stub> {
+        let mut stubs = Vec::new();
+        for (i, word) in words.iter_mut().enumerate() {
+            let mut count: HashMap<String, usize> = HashMap::new();
+            for line in word.take_line_stubs() {
+                let letter = line.pord1().get_anchor().and_then(|anchor| anchor.upgrade());
+                let letter_id = letter.as_ref().and_then(|letter| word.letter_svg_id(letter)).unwrap_or_else(|| word.id());
+                let num = count.entry(letter_id.clone()).or_default();
+                let id = format!("{}-line{}", letter_id, num);
+                *num += 1;
+                stubs.push(Stub { line, word: i, letter, letter_id, id });
+            }
+        }
+        stubs
+    }
+    fn obstacles(&self, words:&mut [W]) -> Vec<Obstacle> {
+        let mut obstacles = Vec::new();
+        let obstacle = |pord:Rc<PordOrCord>, radius:f32, owner:Owner| {
+            Obstacle { radius: radius*pord.frame().1, pord, owner }
+        };
+        for (i, word) in words.iter_mut().enumerate() {
+            obstacles.push(obstacle(word.pord(), word.radius(), Owner::Word(i)));
+            let word_pord = word.pord();
+            for letter in word.arcs().iter() {
+                obstacles.push(obstacle(letter.pord(), letter.radius(), Owner::Letter(letter.pord())));
+                if let Some(vowel) = letter.vowel() {
+                    obstacles.push(obstacle(vowel.pord(), vowel.radius(), Owner::Letter(letter.pord())));
+                }
+                if let Decoration::Dots(num) = letter.decoration() {
+                    //the dot radius comes back scaled already
+                    let (dots, radius) = decorator::letter_dots(&word_pord, &letter.pord(), letter.radius(), num, letter.crowded());
+                    for dot in dots {
+                        obstacles.push(Obstacle { pord: dot, radius, owner: Owner::Letter(letter.pord()) });
+                    }
+                }
+            }
+            for vowel in word.vowels().iter() {
+                obstacles.push(obstacle(vowel.pord(), vowel.radius(), Owner::Nothing));
+            }
+        }
+        obstacles
+    }
+    //greedily joins the closest pairs of line ends that face each other, the rest run out to the ring
+    fn draw_lines(&self, mut doc:Document, stubs:Vec<Stub>, obstacles:&[Obstacle]) -> Document {
+        let origin = self.default_ctx.origin();
+        let ends: Vec<((f32,f32),(f32,f32))> = stubs.iter().map(|stub| {
+            let (start, end) = (stub.line.pord1().abs_svg_xy(origin), stub.line.pord2().abs_svg_xy(origin));
+            let len = (end.0 - start.0).hypot(end.1 - start.1).max(f32::EPSILON);
+            (end, ((end.0 - start.0)/len, (end.1 - start.1)/len))
+        }).collect();
+        let mut options = Vec::new();
+        for (a, (end_a, dir_a)) in ends.iter().enumerate() {
+            options.push((self.ring_dist(*end_a, *dir_a), a, None));
+            for (b, (end_b, dir_b)) in ends.iter().enumerate().skip(a + 1) {
+                if stubs[a].word == stubs[b].word {
+                    continue;
+                }
+                let (dx, dy) = (end_b.0 - end_a.0, end_b.1 - end_a.1);
+                let dist = dx.hypot(dy);
+                let facing = dir_a.0*dx + dir_a.1*dy > LINE_MATCH_COS*dist && -(dir_b.0*dx + dir_b.1*dy) > LINE_MATCH_COS*dist;
+                if facing {
+                    options.push((dist, a, Some(b)));
+                }
+            }
+        }
+        options.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut done = vec![false; stubs.len()];
+        for (_, a, partner) in options {
+            if done[a] || partner.is_some_and(|b| done[b]) {
+                continue;
+            }
+            done[a] = true;
+            doc = match partner {
+                Some(b) => {
+                    done[b] = true;
+                    self.draw_join(doc, &stubs[a], &stubs[b], obstacles)
+                }
+                None => self.draw_to_ring(doc, &stubs[a], obstacles),
+            };
+        }
+        doc
+    }
+    //along the stub if that's clear, otherwise the closest bit of ring either side that can be reached without crossing anything.
+    //if there's none the stub is left as it is
+    fn draw_to_ring(&self, doc:Document, stub:&Stub, obstacles:&[Obstacle]) -> Document {
+        let mark = doc.get_children().len();
+        let origin = self.default_ctx.origin();
+        let (start, end) = (stub.line.pord1().abs_svg_xy(origin), stub.line.pord2().abs_svg_xy(origin));
+        let len = (end.0 - start.0).hypot(end.1 - start.1).max(f32::EPSILON);
+        let dir = ((end.0 - start.0)/len, (end.1 - start.1)/len);
+        let dist = self.ring_dist(end, dir);
+        let ahead = (end.0 + dist*dir.0, end.1 + dist*dir.1);
+        let centre = self.pord.abs_svg_xy(origin);
+        let ahead_angle = (ahead.0 - centre.0).atan2(ahead.1 - centre.1);
+        let inner = self.inner_radius()*self.pord.frame().1;
+        let ring_xy = (0..=RING_SEARCH_STEPS)
+            .flat_map(|i| [1.0, -1.0].map(|side| ahead_angle + side*i as f32*PI/(2.0*RING_SEARCH_STEPS as f32)))
+            .map(|angle| (centre.0 + inner*angle.sin(), centre.1 + inner*angle.cos()))
+            .find(|to| !in_the_way(obstacles, &[stub], &segment(start, *to), origin));
+        let Some(ring_xy) = ring_xy else {
+            return self.group_line(stub.line.clone().draw(doc), mark, stub, None)
+        };
+        let mut builder = Linebuilder::new(stub.line.ctx());
+        _ = builder.add_pord(stub.line.pord1());
+        _ = builder.add_pord(self.pord_at(ring_xy));
+        let line: StraightLine = builder.try_into().expect("both ends were just added");
+        self.group_line(line.draw(doc), mark, stub, Some(&self.id()))
+    }
+    //straight if nothing's in the way, otherwise the first bend either side that misses everything.
+    //if they all hit something both lines run out to the ring on their own instead
+    fn draw_join(&self, doc:Document, a:&Stub, b:&Stub, obstacles:&[Obstacle]) -> Document {
+        let mark = doc.get_children().len();
+        let origin = self.default_ctx.origin();
+        let (p1, p2) = (a.line.pord1().abs_svg_xy(origin), b.line.pord1().abs_svg_xy(origin));
+        let in_the_way = |points:&[(f32,f32)]| in_the_way(obstacles, &[a, b], points, origin);
+        let mut builder = Linebuilder::new(a.line.ctx());
+        _ = builder.add_pord(a.line.pord1());
+        _ = builder.add_pord(b.line.pord1());
+        if !in_the_way(&segment(p1, p2)) {
+            let line: StraightLine = builder.try_into().expect("both ends were just added");
+            return self.group_line(line.draw(doc), mark, a, Some(&b.letter_id))
+        }
+        for bend in LINE_BENDS {
+            for flipped in [false, true] {
+                let (from, to) = if flipped {(p2, p1)} else {(p1, p2)};
+                let (centre, points) = arc_through(from, to, bend);
+                if !in_the_way(&points) {
+                    let mut builder = builder.clone();
+                    if flipped {
+                        builder.switch_pord_1_2();
+                    }
+                    _ = builder.add_pord(self.pord_at(centre));
+                    let line: CirculcarLine = builder.try_into().expect("all three points were just added");
+                    return self.group_line(line.draw_small(doc), mark, a, Some(&b.letter_id))
+                }
+            }
+        }
+        let doc = self.draw_to_ring(doc, a, obstacles);
+        self.draw_to_ring(doc, b, obstacles)
+    }
+    //named after the letter it starts from, with what's at the far end unless it stops short as a stub
+    fn group_line(&self, doc:Document, mark:usize, from:&Stub, to:Option<&str>) -> Document {
+        let mut group = Group::new().set("id", from.id.as_str()).set("class", "line").set("data-from", from.letter_id.as_str());
+        if let Some(to) = to {
+            group = group.set("data-to", to);
+        }
+        basic::group_since(doc, mark, group)
+    }
+    //how far along dir from xy until it meets the inside of the ring
+    fn ring_dist(&self, xy:(f32,f32), dir:(f32,f32)) -> f32 {
+        let centre = self.pord.abs_svg_xy(self.default_ctx.origin());
+        let (x, y) = (xy.0 - centre.0, xy.1 - centre.1);
+        let b = x*dir.0 + y*dir.1;
+        let c = x*x + y*y - (self.inner_radius()*self.pord.frame().1).powi(2);
+        (-b + (b*b - c).max(0.0).sqrt()).max(0.0)
+    }
+    fn pord_at(&self, xy:(f32,f32)) -> Rc<PordOrCord> {
+        Rc::new(PordOrCord::Pord(geometry::pord_at(&self.pord, xy.into(), self.default_ctx.origin())))
+    }
+    //halfway between the word and the one after it
+    fn mark_angle(&self, after_word:usize) -> f32 {
+        let Some(word) = self.words.get(after_word) else {
+            return 0.0
+        };
+        let angle = self.pord.angle_to(word);
+        let next = self.pord.angle_to(&self.words[(after_word + 1) % self.words.len()]);
+        let next = if next <= angle {next + 2.0*std::f32::consts::PI} else {next};
+        (angle + next)/2.0
+    }
+    fn draw_mark(&self, mut doc:Document, mark:Punctuation, theta:f32) -> Document {
+        let inner = self.inner_radius();
+        let size = self.ring_gap/2.0;
+        //sizes on the page, positions go through at so they're scaled already
+        let (dot, small) = (size*self.pord.frame().1, size*self.pord.frame().1/2.0);
+        let filled = self.default_ctx.new_filled();
+        let at = |dist:f32, angle:f32| Rc::new(PordOrCord::Pord(POrd::new(dist, angle, self.pord.clone())));
+        //angles for a row of marks centred on theta
+        let spread = |num:usize| (0..num).map(move |i| theta + (i as f32 - (num - 1) as f32/2.0)*2.5*size/inner);
+        match mark {
+            Punctuation::Period => basic::circle(doc, &at(inner, theta), dot, &self.default_ctx),
+            Punctuation::Colon => {
+                doc = basic::circle(doc, &at(inner, theta), dot, &self.default_ctx);
+                basic::circle(doc, &at(inner, theta), small, &self.default_ctx)
+            }
+            Punctuation::Comma => basic::circle(doc, &at(inner, theta), dot, &filled),
+            Punctuation::Semicolon => basic::circle(doc, &at(inner - 2.0*size, theta), small, &filled),
+            Punctuation::Question | Punctuation::Exclamation => {
+                let num = if mark == Punctuation::Question {2} else {3};
+                for angle in spread(num) {
+                    doc = basic::circle(doc, &at(inner - 2.0*size, angle), small, &filled);
+                }
+                doc
+            }
+            Punctuation::Quote | Punctuation::Hyphen => {
+                for line in self.mark_lines(mark, theta) {
+                    doc = line.draw(doc);
+                }
+                doc
+            }
+        }
+    }
+    //the strokes of a quote or hyphen, reaching out past the ring
+    fn mark_lines(&self, mark:Punctuation, theta:f32) -> Vec<StraightLine> {
+        let inner = self.inner_radius();
+        let size = self.ring_gap/2.0;
+        let line_ctx = self.default_ctx.new_strokewidth(self.default_ctx.stroke().strokewidth()/2.0);
+        let num = match mark {
+            Punctuation::Quote => 1,
+            Punctuation::Hyphen => 3,
+            _ => return Vec::new(),
+        };
+        (0..num).map(|i| {
+            let angle = theta + (i as f32 - (num - 1) as f32/2.0)*2.5*size/inner;
+            let mut builder = Linebuilder::new(&line_ctx);
+            _ = builder.add_pord(Rc::new(PordOrCord::Pord(POrd::new(inner, angle, self.pord.clone()))));
+            _ = builder.add_pord(Rc::new(PordOrCord::Pord(POrd::new(self.radius + self.ring_gap, angle, self.pord.clone()))));
+            builder.try_into().expect("both ends were just added")
+        }).collect()
+    }
+}
+
+//the outer ring, the words & any marks sticking out past the ring. joining lines stay inside the ring
+impl<W:Word + Bounded> Bounded for SentenceCircle<W> {
+    fn bounding_box(&self) -> BBox {
+        let ring = basic::circle_bounds(self.pord.as_ref(), self.radius*self.pord.frame().1, &self.default_ctx);
+        let words = self.words.iter().map(|word| word.bounding_box());
+        let marks = self.punctuation.iter()
+            .flat_map(|(after, mark)| self.mark_lines(*mark, self.mark_angle(*after)))
+            .map(|line| line.bounding_box());
+        words.chain(marks).fold(ring, |total, found| total.union(&found))
+    }
+}
+
+impl SentenceCircle<WordCircle> {
+    pub fn from_text(text:&str, pord:Rc<PordOrCord>, radius:f32, layout:&WordLayout, ctx:Context) -> Result<SentenceCircle<WordCircle>, GallifreyanError> {
+        let mut sentence = SentenceCircle::new(text, pord, radius, ctx.clone());
+        let mut words = Vec::new();
+        let mut marks = Vec::new();
+        for token in text.split_whitespace() {
+            let glyphs = translate::translate_word(token);
+            if !glyphs.is_empty() {
+                words.push((token.trim_matches(|c:char| !c.is_alphanumeric()), glyphs));
+            }
+            let mark = token.chars().last().and_then(Punctuation::from_char);
+            if let (Some(mark), Some(after)) = (mark, words.len().checked_sub(1)) {
+                marks.push((after, mark));
+            }
+        }
+        let counts: Vec<usize> = words.iter().map(|(_, glyphs)| glyphs.len()).collect();
+        for ((word, glyphs), (word_pord, word_radius)) in words.iter().zip(sentence.word_slots(&counts)?) {
+            let word = layout.word_circle_from_glyphs(word, word_pord, word_radius, glyphs, ctx.clone());
+            //a word on its own has the whole ring, otherwise it's the others leaving too little room
+            let word = match word {
+                Err(GallifreyanError::GeometryUnsolvable(_)) if counts.len() > 1 => return Err(GallifreyanError::GeometryUnsolvable("words don't fit the sentence ring".to_string())),
+                word => word?,
+            };
+            sentence.push_word(word);
+        }
+        for (after, mark) in marks {
+            sentence.add_punctuation(after, mark);
+        }
+        Ok(sentence)
+    }
+}
+
+//words are numbered until nothing they'd write clashes with an id that's already taken
+fn unique_word_ids<W:Word>(words:&mut [W], mut taken:HashSet<String>) {
+    for word in words.iter_mut() {
+        let id = word.id();
+        let mut count = 1;
+        while word.svg_ids().iter().any(|used| taken.contains(used)) {
+            count += 1;
+            word.set_id(&format!("{}-{}", id, count));
+        }
+        taken.extend(word.svg_ids());
+    }
+}
+
+//whether any point lands in a letter or word, other than the ones the lines come from
+fn in_the_way(obstacles:&[Obstacle], stubs:&[&Stub], points:&[(f32,f32)], origin:(f32,f32)) -> bool {
+    obstacles.iter()
+        .filter(|obs| match &obs.owner {
+            Owner::Word(word) => stubs.iter().all(|stub| stub.word != *word),
+            Owner::Letter(letter) => !stubs.iter().any(|stub| stub.letter.as_ref().is_some_and(|own| Rc::ptr_eq(own, letter))),
+            Owner::Nothing => true,
+        })
+        .any(|obs| {
+            let xy = obs.pord.abs_svg_xy(origin);
+            points.iter().any(|p| (p.0 - xy.0).hypot(p.1 - xy.1) < obs.radius)
+        })
+}
+
+fn segment(p1:(f32,f32), p2:(f32,f32)) -> Vec<(f32,f32)> {
+    (0..=LINE_SAMPLES).map(|i| {
+        let t = i as f32/LINE_SAMPLES as f32;
+        (p1.0 + t*(p2.0 - p1.0), p1.1 + t*(p2.1 - p1.1))
+    }).collect()
+}
+
+//centre & points along the small arc CirculcarLine draws from p1 to p2, bowed out by bend times their distance
+fn arc_through(p1:(f32,f32), p2:(f32,f32), bend:f32) -> ((f32,f32), Vec<(f32,f32)>) {
+    let (dx, dy) = (p2.0 - p1.0, p2.1 - p1.1);
+    let len = dx.hypot(dy).max(f32::EPSILON);
+    //sweep 0 puts the centre on this side of p1 -> p2
+    let (nx, ny) = (dy/len, -dx/len);
+    let centre = ((p1.0 + p2.0)/2.0 + nx*bend*len, (p1.1 + p2.1)/2.0 + ny*bend*len);
+    let (a1, a2) = ((p1.0 - centre.0, p1.1 - centre.1), (p2.0 - centre.0, p2.1 - centre.1));
+    let sweep = (a1.0*a2.1 - a1.1*a2.0).atan2(a1.0*a2.0 + a1.1*a2.1);
+    let points = (0..=LINE_SAMPLES).map(|i| {
+        let (sin, cos) = (sweep*i as f32/LINE_SAMPLES as f32).sin_cos();
+        (centre.0 + a1.0*cos - a1.1*sin, centre.1 + a1.0*sin + a1.1*cos)
+    }).collect();
+    (centre, points)
+}
@@ -0,0 +1,362 @@
+use std::fmt::Write;
+use std::io::Error;
+
+use crate::cutter::Outline;
+use crate::utils;
+
+const DEFAULT_HEIGHT :f32 = 2.0;
+//points round a round base, the machine's tolerance sets everything else
+const BASE_POINTS :usize = 180;
+
+type Point = (f32,f32);
+type Vertex = [f32;3];
+
+//a plate under the whole drawing, thickness in mm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Base {
+    None,
+    //the size of the canvas
+    Rectangle(f32),
+    //as big as fits on the canvas, for coasters & pendants
+    Disc(f32),
+}
+//heights in mm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StlOptions {
+    height:f32,
+    base:Base,
+    binary:bool,
+}
+//triangles facing out, counter clockwise seen from outside
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    name:String,
+    triangles:Vec<[Vertex;3]>,
+}
+
+impl Default for StlOptions {
+    fn default() -> Self {
+        Self { height: DEFAULT_HEIGHT, base: Base::None, binary: true }
+    }
+}
+
+impl StlOptions {
+    pub fn new(height:f32) -> StlOptions {
+        StlOptions { height, ..Default::default() }
+    }
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+    pub fn base(&self) -> Base {
+        self.base
+    }
+    pub fn binary(&self) -> bool {
+        self.binary
+    }
+    //how far the drawing stands up, off the base if there is one
+    pub fn set_height(&mut self, height:f32) {
+        self.height = height;
+    }
+    pub fn set_base(&mut self, base:Base) {
+        self.base = base;
+    }
+    pub fn set_binary(&mut self, binary:bool) {
+        self.binary = binary;
+    }
+}
+
+impl Mesh {
+    //every shape in the outline stood up as a solid of its own, overlapping ones included, slicers join them
+    //fails if a shape can't be cut into triangles cleanly, rather than leave holes or overlaps in the mesh
+    pub fn new(outline:&Outline, options:&StlOptions) -> Result<Mesh, Error> {
+        let mut mesh = Mesh { name: outline.title().unwrap_or("gallifreyan").to_string(), triangles: Vec::new() };
+        let (width, height) = {
+            let (_, _, width, height) = outline.view_box();
+            let scale = outline.machine().scale(outline.view_box());
+            (width*scale, height*scale)
+        };
+        let bottom = match options.base {
+            Base::None => 0.0,
+            Base::Rectangle(thickness) => {
+                mesh.extrude(&[vec![(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)]], 0.0, thickness)?;
+                thickness
+            }
+            Base::Disc(thickness) => {
+                let (radius, centre) = (width.min(height)/2.0, (width/2.0, height/2.0));
+                let disc = utils::ang_iter(BASE_POINTS).map(|angle| (centre.0 + radius*angle.cos(), centre.1 + radius*angle.sin())).collect();
+                mesh.extrude(&[disc], 0.0, thickness)?;
+                thickness
+            }
+        };
+        for (num, shape) in outline.shapes().iter().enumerate() {
+            mesh.extrude(shape, bottom, bottom + options.height)
+                .map_err(|_| Error::other(format!("shape {} couldn't be cut into triangles", num)))?;
+        }
+        Ok(mesh)
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn triangles(&self) -> &[[Vertex;3]] {
+        &self.triangles
+    }
+    //a flat shape, evenodd between its contours, made solid from z `bottom` to `top`
+    fn extrude(&mut self, contours:&[Vec<Point>], bottom:f32, top:f32) -> Result<(), Error> {
+        let contours: Vec<Vec<Point>> = contours.iter().map(|contour| clean(contour)).filter(|contour| contour.len() > 2).collect();
+        let triangles = triangulate(&contours).ok_or_else(|| Error::other("ear clipping got stuck"))?;
+        for [a, b, c] in triangles {
+            self.triangles.push([[a.0, a.1, top], [b.0, b.1, top], [c.0, c.1, top]]);
+            self.triangles.push([[a.0, a.1, bottom], [c.0, c.1, bottom], [b.0, b.1, bottom]]);
+        }
+        //walls, with the solid on the left of each side
+        for (i, contour) in contours.iter().enumerate() {
+            let outer = depth(&contours, i).is_multiple_of(2);
+            let contour: Vec<Point> = if outer == (area(contour) > 0.0) {contour.clone()} else {contour.iter().rev().copied().collect()};
+            for (a, b) in contour.iter().zip(contour.iter().cycle().skip(1)) {
+                self.triangles.push([[a.0, a.1, bottom], [b.0, b.1, bottom], [b.0, b.1, top]]);
+                self.triangles.push([[a.0, a.1, bottom], [b.0, b.1, top], [a.0, a.1, top]]);
+            }
+        }
+        Ok(())
+    }
+    pub fn ascii(&self) -> String {
+        let mut out = String::new();
+        let name = self.name.replace(|c:char| !c.is_ascii_graphic(), "_");
+        _ = writeln!(out, "solid {}", name);
+        for triangle in &self.triangles {
+            let [nx, ny, nz] = normal(triangle);
+            _ = writeln!(out, "  facet normal {:e} {:e} {:e}", nx, ny, nz);
+            _ = writeln!(out, "    outer loop");
+            for [x, y, z] in triangle {
+                _ = writeln!(out, "      vertex {:e} {:e} {:e}", x, y, z);
+            }
+            _ = writeln!(out, "    endloop");
+            _ = writeln!(out, "  endfacet");
+        }
+        _ = writeln!(out, "endsolid {}", name);
+        out
+    }
+    //80 byte header, which mustn't start with "solid", then the count & 50 bytes a triangle
+    pub fn binary(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(84 + 50*self.triangles.len());
+        let mut header = format!("gallifreyan {}", self.name).into_bytes();
+        header.resize(80, b' ');
+        out.extend(header);
+        out.extend((self.triangles.len() as u32).to_le_bytes());
+        for triangle in &self.triangles {
+            for value in normal(triangle).iter().chain(triangle.iter().flatten()) {
+                out.extend(value.to_le_bytes());
+            }
+            out.extend(0u16.to_le_bytes());
+        }
+        out
+    }
+}
+
+//the outline stood up as an stl, binary or ascii as the options say
+pub fn stl(outline:&Outline, options:&StlOptions) -> Result<Vec<u8>, Error> {
+    let mesh = Mesh::new(outline, options)?;
+    Ok(if options.binary {
+        mesh.binary()
+    } else {
+        mesh.ascii().into_bytes()
+    })
+}
+
+fn normal([a, b, c]:&[Vertex;3]) -> Vertex {
+    let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+    let n = [u[1]*v[2] - u[2]*v[1], u[2]*v[0] - u[0]*v[2], u[0]*v[1] - u[1]*v[0]];
+    let length = (n[0].powi(2) + n[1].powi(2) + n[2].powi(2)).sqrt();
+    if length == 0.0 {
+        return [0.0, 0.0, 0.0]
+    }
+    [n[0]/length, n[1]/length, n[2]/length]
+}
+
+//without repeated points, or the first again at the end
+fn clean(contour:&[Point]) -> Vec<Point> {
+    let mut result: Vec<Point> = Vec::with_capacity(contour.len());
+    for point in contour {
+        if result.last().is_none_or(|last| last != point) {
+            result.push(*point);
+        }
+    }
+    while result.len() > 1 && result.first() == result.last() {
+        result.pop();
+    }
+    result
+}
+
+//positive when counter clockwise
+fn area(points:&[Point]) -> f32 {
+    points.iter().zip(points.iter().cycle().skip(1)).map(|(a, b)| a.0*b.1 - b.0*a.1).sum::<f32>()/2.0
+}
+
+fn cross(a:Point, b:Point, c:Point) -> f32 {
+    (b.0 - a.0)*(c.1 - a.1) - (b.1 - a.1)*(c.0 - a.0)
+}
+
+fn contains(points:&[Point], p:Point) -> bool {
+    let mut inside = false;
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1)*(b.0 - a.0)/(b.1 - a.1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+//how many of the other contours it's inside, even for outsides & odd for holes
+fn depth(contours:&[Vec<Point>], i:usize) -> usize {
+    contours.iter().enumerate().filter(|(j, other)| *j != i && contains(other, contours[i][0])).count()
+}
+
+//each outside with the holes directly inside it, cut into triangles counter clockwise. None if one of them won't go
+fn triangulate(contours:&[Vec<Point>]) -> Option<Vec<[Point;3]>> {
+    let depths: Vec<usize> = (0..contours.len()).map(|i| depth(contours, i)).collect();
+    let mut result = Vec::new();
+    for (i, outer) in contours.iter().enumerate().filter(|(i, _)| depths[*i].is_multiple_of(2)) {
+        let mut holes: Vec<Vec<Point>> = contours.iter().enumerate()
+            .filter(|(j, hole)| depths[*j] == depths[i] + 1 && contains(outer, hole[0]))
+            .map(|(_, hole)| if area(hole) > 0.0 {hole.iter().rev().copied().collect()} else {hole.clone()})
+            .collect();
+        //rightmost first, so each one joins to whatever's right of it
+        holes.sort_by(|a, b| rightmost(b).1.0.total_cmp(&rightmost(a).1.0));
+        let mut polygon = if area(outer) < 0.0 {outer.iter().rev().copied().collect()} else {outer.clone()};
+        for hole in holes {
+            polygon = join_hole(polygon, hole);
+        }
+        result.extend(ear_clip(polygon)?);
+    }
+    Some(result)
+}
+
+fn rightmost(points:&[Point]) -> (usize, Point) {
+    points.iter().copied().enumerate().max_by(|a, b| a.1.0.total_cmp(&b.1.0)).expect("contours aren't empty")
+}
+
+//cuts from the hole's rightmost point across to a point on the outside it can see, making one polygon
+fn join_hole(outer:Vec<Point>, hole:Vec<Point>) -> Vec<Point> {
+    let (m_index, m) = rightmost(&hole);
+    let len = outer.len();
+    //nearest side straight to the right
+    let mut nearest: Option<(f32, usize)> = None;
+    for i in 0..len {
+        let (a, b) = (outer[i], outer[(i + 1) % len]);
+        if a.1 == b.1 || (a.1 > m.1) == (b.1 > m.1) {
+            continue
+        }
+        let x = a.0 + (m.1 - a.1)*(b.0 - a.0)/(b.1 - a.1);
+        if x >= m.0 && nearest.is_none_or(|(best, _)| x < best) {
+            nearest = Some((x, i));
+        }
+    }
+    let Some((x, i)) = nearest else {
+        return outer
+    };
+    let hit = (x, m.1);
+    let mut p_index = if outer[i].0 > outer[(i + 1) % len].0 {i} else {(i + 1) % len};
+    //anything poking into the triangle between would block the view, the one closest to the ray does instead
+    let p = outer[p_index];
+    let turn = cross(m, hit, p);
+    let (t1, t2) = if turn > 0.0 {(hit, p)} else {(p, hit)};
+    let mut best = f32::MAX;
+    for (j, q) in outer.iter().enumerate() {
+        if turn != 0.0 && j != p_index && q.0 > m.0 && in_triangle(*q, m, t1, t2) {
+            let angle = (q.1 - m.1).abs()/(q.0 - m.0).max(f32::EPSILON);
+            if angle < best {
+                best = angle;
+                p_index = j;
+            }
+        }
+    }
+    let mut result = Vec::with_capacity(len + hole.len() + 2);
+    result.extend_from_slice(&outer[..=p_index]);
+    result.extend(hole[m_index..].iter().chain(&hole[..=m_index]));
+    result.extend_from_slice(&outer[p_index..]);
+    result
+}
+
+fn in_triangle(p:Point, a:Point, b:Point, c:Point) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+//counter clockwise polygon, holes already joined on, into triangles. None if it goes all the way round without finding an ear
+fn ear_clip(mut points:Vec<Point>) -> Option<Vec<[Point;3]>> {
+    let mut result = Vec::with_capacity(points.len());
+    let (mut i, mut tries) = (0, 0);
+    while points.len() > 3 {
+        let len = points.len();
+        i %= len;
+        let (a, b, c) = (points[(i + len - 1) % len], points[i], points[(i + 1) % len]);
+        let turn = cross(a, b, c);
+        if turn.abs() <= f32::EPSILON*(a.0.abs() + a.1.abs() + 1.0) {
+            //nothing there, in a straight line or back on itself
+            points.remove(i);
+            tries = 0;
+            continue
+        }
+        let ear = turn > 0.0 && !points.iter().any(|p| *p != a && *p != b && *p != c && in_triangle(*p, a, b, c));
+        if ear {
+            result.push([a, b, c]);
+            points.remove(i);
+            tries = 0;
+        } else if tries > len {
+            return None
+        } else {
+            i += 1;
+            tries += 1;
+        }
+    }
+    if let [a, b, c] = points[..] && cross(a, b, c) > 0.0 {
+        result.push([a, b, c]);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle(radius:f32, centre:Point, clockwise:bool) -> Vec<Point> {
+        let points: Vec<Point> = utils::ang_iter(64).map(|angle| (centre.0 + radius*angle.cos(), centre.1 + radius*angle.sin())).collect();
+        if clockwise {points.into_iter().rev().collect()} else {points}
+    }
+
+    fn covered(contours:&[Vec<Point>]) -> f32 {
+        triangulate(contours).expect("these all triangulate").iter().map(|[a, b, c]| cross(*a, *b, *c)/2.0).sum()
+    }
+
+    fn assert_close(a:f32, b:f32) {
+        assert!((a - b).abs() < 1e-3*b.abs(), "{} isn't {}", a, b);
+    }
+
+    #[test]
+    fn ring() {
+        let outer = circle(10.0, (3.0, -2.0), false);
+        assert_close(covered(std::slice::from_ref(&outer)), area(&outer));
+        //either way round
+        let backwards = circle(10.0, (3.0, -2.0), true);
+        assert_close(covered(&[backwards]), area(&outer));
+    }
+
+    #[test]
+    fn ring_with_hole() {
+        let (outer, hole) = (circle(10.0, (0.0, 0.0), false), circle(6.0, (1.0, 0.5), false));
+        assert_close(covered(&[outer.clone(), hole.clone()]), area(&outer) - area(&hole));
+    }
+
+    #[test]
+    fn island_in_a_hole() {
+        let (outer, hole, island) = (circle(10.0, (0.0, 0.0), false), circle(6.0, (0.0, 0.0), true), circle(3.0, (0.5, 0.0), false));
+        let contours = [outer.clone(), hole.clone(), island.clone()];
+        assert_eq!((0..3).map(|i| depth(&contours, i)).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_close(covered(&contours), area(&outer) + area(&hole) + area(&island));
+    }
+
+    #[test]
+    fn no_ears_is_an_error() {
+        //ear_clip wants counter clockwise, this way round nothing's an ear
+        assert!(ear_clip(vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)]).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use std::f32::consts::PI;
 use std::io::Error;
+use std::path::Path;
 use std::rc::Rc;
 
 use gallifreyan::pord::PordOrCord::{self,Pord,Gord};
@@ -19,7 +20,7 @@ const LETTER_RADIUS :f32 = 40.0;
 
 pub fn test(filename:&str) -> Result<Document, Error> {
     //let filename = "test2";
-    let filepath = "Imgs\\".to_owned() + &filename.trim();
+    let filepath = Path::new("Imgs").join(filename.trim()).display().to_string();
     println!("Starting...");
     let (mut doc, svg_origin) = Gal::canvas_init(WIDTH, HEIGHT, canvas_colour());
     let origin = PordOrCord::gal_origin(svg_origin);
@@ -27,7 +28,7 @@ pub fn test(filename:&str) -> Result<Document, Error> {
     let colour2 = ColourContext::new("white","none","red");
     let mut stroke = StrokeContext::new(20.0);
     let prime_ctx = Context::new(colour,stroke,origin.clone());
-    let thick_ctx = prime_ctx.new_strokewidth(30.0);
+    let _thick_ctx = prime_ctx.new_strokewidth(30.0);
     let word_ctx = prime_ctx.new_strokewidth(10.0); 
     let lett_ctx =  prime_ctx.new_strokewidth(8.0); 
 
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Decoration, StemType};
+use crate::Decoration::{Dots, Lines};
+use crate::StemType::{B, J, S, Z};
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VowelKind {A,E,I,O,U}
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum GlyphKind {
+    Consonant(StemType, Decoration),
+    Vowel(VowelKind),
+}
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Glyph {
+    text:String,
+    kind:GlyphKind,
+}
+
+//Sherman's table, one row per stem type
+const CONSONANTS: [(&str, StemType, Decoration); 24] = [
+    ("b", B, Decoration::None), ("ch", B, Dots(2)), ("d", B, Dots(3)), ("f", B, Lines(3)), ("g", B, Lines(1)), ("h", B, Lines(2)),
+    ("j", J, Decoration::None), ("k", J, Dots(2)), ("l", J, Dots(3)), ("m", J, Lines(3)), ("n", J, Lines(1)), ("p", J, Lines(2)),
+    ("t", S, Decoration::None), ("sh", S, Dots(2)), ("r", S, Dots(3)), ("s", S, Lines(3)), ("v", S, Lines(1)), ("w", S, Lines(2)),
+    ("th", Z, Decoration::None), ("y", Z, Dots(2)), ("z", Z, Dots(3)), ("ng", Z, Lines(3)), ("qu", Z, Lines(1)), ("x", Z, Lines(2)),
+];
+const VOWELS: [(&str, VowelKind); 5] = [
+    ("a", VowelKind::A), ("e", VowelKind::E), ("i", VowelKind::I), ("o", VowelKind::O), ("u", VowelKind::U),
+];
+const DIGRAPHS: [&str; 5] = ["th", "sh", "ch", "ng", "qu"];
+
+impl Glyph {
+    pub fn consonant(text:&str) -> Option<Glyph> {
+        let (text, stem_type, decoration) = CONSONANTS.iter().find(|(t,_,_)| *t == text)?;
+        Some(Glyph { text: text.to_string(), kind: GlyphKind::Consonant(*stem_type, *decoration) })
+    }
+    pub fn vowel(text:&str) -> Option<Glyph> {
+        let (text, kind) = VOWELS.iter().find(|(t,_)| *t == text)?;
+        Some(Glyph { text: text.to_string(), kind: GlyphKind::Vowel(*kind) })
+    }
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    pub fn kind(&self) -> GlyphKind {
+        self.kind
+    }
+    pub fn stem_type(&self) -> Option<StemType> {
+        match self.kind {
+            GlyphKind::Consonant(stem_type, _) => Some(stem_type),
+            GlyphKind::Vowel(_) => None,
+        }
+    }
+    pub fn decoration(&self) -> Decoration {
+        match self.kind {
+            GlyphKind::Consonant(_, decoration) => decoration,
+            GlyphKind::Vowel(_) => Decoration::None,
+        }
+    }
+    pub fn vowel_kind(&self) -> Option<VowelKind> {
+        match self.kind {
+            GlyphKind::Vowel(kind) => Some(kind),
+            GlyphKind::Consonant(..) => None,
+        }
+    }
+    pub fn is_vowel(&self) -> bool {
+        self.vowel_kind().is_some()
+    }
+    //the letter it's drawn as, which isn't always how it was spelt
+    pub fn sound(&self) -> &'static str {
+        match self.kind {
+            GlyphKind::Consonant(stem_type, decoration) => CONSONANTS.iter()
+                .find(|(_, s, d)| *s == stem_type && *d == decoration)
+                .map(|(text,_,_)| *text)
+                .expect("every consonant is in the table"),
+            GlyphKind::Vowel(kind) => VOWELS.iter()
+                .find(|(_, k)| *k == kind)
+                .map(|(text,_)| *text)
+                .expect("every vowel is in the table"),
+        }
+    }
+    //keeps the original spelling, for letters like c that borrow another glyph
+    fn spelt(mut self, text:&str) -> Glyph {
+        self.text = text.to_string();
+        self
+    }
+}
+
+//every consonant in table order, then the vowels
+pub fn alphabet() -> Vec<Glyph> {
+    let consonants = CONSONANTS.iter().filter_map(|(text,_,_)| Glyph::consonant(text));
+    let vowels = VOWELS.iter().filter_map(|(text,_)| Glyph::vowel(text));
+    consonants.chain(vowels).collect()
+}
+
+//splits on whitespace, anything that isn't a letter is dropped
+pub fn translate(text:&str) -> Vec<Vec<Glyph>> {
+    text.split_whitespace()
+        .map(translate_word)
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+//the letters a word is drawn with, dash separated, like "k-a-t" for cat
+pub fn transliterate(glyphs:&[Glyph]) -> String {
+    glyphs.iter().map(Glyph::sound).collect::<Vec<_>>().join("-")
+}
+
+pub fn transliterate_text(text:&str) -> String {
+    translate(text).iter().map(|word| transliterate(word)).collect::<Vec<_>>().join(" ")
+}
+
+pub fn translate_word(word:&str) -> Vec<Glyph> {
+    let letters: Vec<char> = word.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .collect();
+    let mut result = Vec::with_capacity(letters.len());
+    let mut i = 0;
+    while i < letters.len() {
+        if let Some(next) = letters.get(i+1) {
+            let pair: String = [letters[i], *next].iter().collect();
+            if DIGRAPHS.contains(&pair.as_str()) {
+                result.push(Glyph::consonant(&pair).expect("digraphs are in the table"));
+                i += 2;
+                continue;
+            }
+        }
+        //ck is a single k sound
+        if letters[i] == 'c' && letters.get(i+1) == Some(&'k') {
+            i += 1;
+            continue;
+        }
+        let letter = letters[i].to_string();
+        let glyph = match letters[i] {
+            //soft c before e, i & y, hard c otherwise
+            'c' => match letters.get(i+1) {
+                Some('e') | Some('i') | Some('y') => Glyph::consonant("s"),
+                _ => Glyph::consonant("k"),
+            }.map(|g| g.spelt("c")),
+            'q' => Glyph::consonant("qu").map(|g| g.spelt("q")),
+            _ => Glyph::vowel(&letter).or_else(|| Glyph::consonant(&letter)),
+        };
+        result.extend(glyph);
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sounds(word:&str) -> String {
+        transliterate(&translate_word(word))
+    }
+
+    #[test]
+    fn digraphs() {
+        assert_eq!(sounds("thing"), "th-i-ng");
+        assert_eq!(sounds("shush"), "sh-u-sh");
+        assert_eq!(sounds("church"), "ch-u-r-ch");
+        //g then n isn't ng
+        assert_eq!(sounds("hang"), "h-a-ng");
+        assert_eq!(sounds("gnat"), "g-n-a-t");
+    }
+
+    #[test]
+    fn soft_and_hard_c() {
+        assert_eq!(sounds("cat"), "k-a-t");
+        assert_eq!(sounds("city"), "s-i-t-y");
+        assert_eq!(sounds("cell"), "s-e-l-l");
+        assert_eq!(sounds("cycle"), "s-y-k-l-e");
+        assert_eq!(sounds("back"), "b-a-k");
+        //c keeps its spelling for data-letter
+        let cat = translate_word("cat");
+        assert_eq!(cat[0].text(), "c");
+        assert_eq!(cat[0].stem_type(), Some(StemType::J));
+    }
+
+    #[test]
+    fn q_is_qu() {
+        assert_eq!(sounds("queen"), "qu-e-e-n");
+        assert_eq!(sounds("qat"), "qu-a-t");
+        assert_eq!(translate_word("qat")[0].text(), "q");
+    }
+
+    #[test]
+    fn punctuation_and_empty() {
+        assert_eq!(sounds("Don't!"), "d-o-n-t");
+        assert!(translate_word("").is_empty());
+        assert!(translate("").is_empty());
+        assert!(translate("... ?! 42").is_empty());
+        assert_eq!(transliterate_text("Hello, world."), "h-e-l-l-o w-o-r-l-d");
+    }
+}
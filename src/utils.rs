@@ -2,10 +2,12 @@
 use std::{f32::consts::PI, rc::Rc};
 
 use svg::node::element::path::Data;
-use svg::node::element::path::Command::{Move, EllipticalArc};
+use svg::node::element::path::Command::{Move, Line, EllipticalArc};
 use svg::node::element::path::Position::Absolute as A;
 
 use crate::pord::{POrd, PordOrCord};
+//most points a flattened arc is split into, however fine the tolerance
+pub(crate) const MAX_ARC_POINTS :usize = 4096;
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct SweepDirection(pub bool);
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
@@ -13,6 +15,7 @@ pub struct LargeArcFlag(pub bool);
 #[derive(Debug, Clone, Copy)]
 pub enum PathParameter{
     Move,
+    Line,
     Arc(f32,LargeArcFlag,SweepDirection)
 }
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -35,10 +38,75 @@ pub fn ang_iter_from_range(num:usize, min:f32, max:f32) -> impl Iterator<Item =
     obj.map(move|i|i as f32 * step + min)
 }
 
+//where two circles cross, if they do
+pub fn circle_crossings(c1:SvgPosition, r1:f32, c2:SvgPosition, r2:f32) -> Option<(SvgPosition,SvgPosition)> {
+    let (dx,dy) = (c2.0 - c1.0, c2.1 - c1.1);
+    let dist = dx.hypot(dy);
+    if dist == 0.0 || dist > r1 + r2 || dist < (r1 - r2).abs() {
+        return None
+    }
+    let along = (dist.powi(2) + r1.powi(2) - r2.powi(2))/(2.0*dist);
+    let across = (r1.powi(2) - along.powi(2)).max(0.0).sqrt();
+    let (ux,uy) = (dx/dist, dy/dist);
+    let (mx,my) = (c1.0 + along*ux, c1.1 + along*uy);
+    Some((SvgPosition(mx - across*uy, my + across*ux), SvgPosition(mx + across*uy, my - across*ux)))
+}
+
+//whether going from start to end round the centre, in the sweep direction, is the long way
+pub fn large_arc(centre:SvgPosition, start:SvgPosition, end:SvgPosition, sweep:SweepDirection) -> LargeArcFlag {
+    let start_ang = (start.1 - centre.1).atan2(start.0 - centre.0);
+    let end_ang = (end.1 - centre.1).atan2(end.0 - centre.0);
+    //svg's positive angle direction is clockwise on screen
+    let span = (end_ang - start_ang).rem_euclid(2.0*PI);
+    LargeArcFlag(if sweep.0 {span} else {2.0*PI - span} > PI)
+}
+
+//points along an svg arc, no more than `tolerance` from the true curve unless that'd take over MAX_ARC_POINTS, not including the start
+pub fn flatten_arc(start:SvgPosition, end:SvgPosition, radius:f32, arc:LargeArcFlag, sweep:SweepDirection, tolerance:f32) -> Vec<SvgPosition> {
+    let (hx, hy) = ((start.0 - end.0)/2.0, (start.1 - end.1)/2.0);
+    let half = hx.hypot(hy);
+    if half == 0.0 {
+        return Vec::new()
+    }
+    //svg stretches radii that are too small to reach
+    let radius = radius.max(half);
+    let offset = (radius.powi(2) - half.powi(2)).max(0.0).sqrt()/half;
+    let sign = if arc.0 != sweep.0 {1.0} else {-1.0};
+    let centre = SvgPosition((start.0 + end.0)/2.0 + sign*offset*hy, (start.1 + end.1)/2.0 - sign*offset*hx);
+    let start_ang = (start.1 - centre.1).atan2(start.0 - centre.0);
+    let end_ang = (end.1 - centre.1).atan2(end.0 - centre.0);
+    let span = if sweep.0 {
+        (end_ang - start_ang).rem_euclid(2.0*PI)
+    } else {
+        -(start_ang - end_ang).rem_euclid(2.0*PI)
+    };
+    //each chord's sagitta stays inside the tolerance
+    let step = 2.0*(1.0 - (tolerance/radius).min(1.0)).acos();
+    let num = ((span.abs()/step.max(f32::EPSILON)).ceil() as usize).clamp(1, MAX_ARC_POINTS);
+    let mut points: Vec<SvgPosition> = (1..num).map(|i| {
+        let angle = start_ang + span*i as f32/num as f32;
+        SvgPosition(centre.0 + radius*angle.cos(), centre.1 + radius*angle.sin())
+    }).collect();
+    points.push(end);
+    points
+}
+
+//lowercase letters & digits, with a dash for each run of anything else, for svg ids
+pub fn id_slug(name:&str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
 pub fn generate_pord_vector(num:usize, pord:Rc<PordOrCord>,radius:f32) -> Vec<POrd> {
     let mut result = Vec::with_capacity(num);
-    let mut angle_gen = ang_iter(num);
-    while let Some(ang) = angle_gen.next() {
+    for ang in ang_iter(num) {
         result.push(POrd::new(radius, ang, pord.clone()))
     }
     result
@@ -58,36 +126,95 @@ impl PathBuilder {
             PathParameter::Arc(radius, arc, sweep)
         );
     }
-    pub fn reverse_and_apphend(self, mut data:Data) -> Data {
+    pub fn line_to(&mut self, to:SvgPosition) {
+        self.positions.push(to);
+        self.parameters.push(PathParameter::Line);
+    }
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+    pub fn push(&mut self, to:SvgPosition, param:PathParameter) {
+        self.positions.push(to);
+        self.parameters.push(param);
+    }
+    pub fn first_position(&self) -> Option<SvgPosition> {
+        self.positions.first().copied()
+    }
+    pub fn last_position(&self) -> Option<SvgPosition> {
+        self.positions.last().copied()
+    }
+    pub fn pop(&mut self) -> Option<(SvgPosition,PathParameter)> {
+        Some((self.positions.pop()?, self.parameters.pop()?))
+    }
+    //drops the first `skip` steps & starts from `at` instead
+    pub fn restart(&mut self, at:SvgPosition, skip:usize) {
+        self.positions.drain(..skip.min(self.positions.len()));
+        self.parameters.drain(..skip.min(self.parameters.len()));
+        self.positions.insert(0, at);
+        self.parameters.insert(0, PathParameter::Move);
+    }
+    //recalculates an arc's large arc flag, once its ends have moved
+    pub fn fit_large_arc(&mut self, index:usize, centre:SvgPosition) {
+        if index == 0 || index >= self.positions.len() {
+            return
+        }
+        let (start, end) = (self.positions[index - 1], self.positions[index]);
+        if let PathParameter::Arc(_, arc, sweep) = &mut self.parameters[index] {
+            *arc = large_arc(centre, start, end, *sweep);
+        }
+    }
+    pub fn reverse_and_apphend(self, data:Data) -> Data {
+        self.reversed().apphend(data)
+    }
+    //the same path, drawn from the other end
+    pub fn reversed(self) -> PathBuilder {
+        let mut result = PathBuilder::new();
         let mut pos_iter = self.positions.into_iter().rev();
         let mut param_iter = self.parameters.into_iter().rev();
         let pos = pos_iter.next().expect("Empty vec?");
-        data = data.add(Move(A, (pos.0,pos.1).into()));
+        result.move_to(pos);
         while let (Some(pos),Some(param)) = (pos_iter.next(), param_iter.next()) {
-            data = match param {
-                PathParameter::Move => {
-                    data.add(Move(A,(pos.0,pos.1).into()))
-                }
-                PathParameter::Arc(radius,LargeArcFlag(arc),SweepDirection(sweep)) => {
-                    data.add(EllipticalArc(A, (
-                        radius,radius,
-                        0.0,
-                        if arc {1.0} else {0.0},
-                        if sweep {0.0} else {1.0}, //swapped since reversed
-                        pos.0,pos.1
-                    ).into()))
+            result.push(pos, match param {
+                //swapped since reversed
+                PathParameter::Arc(radius, arc, SweepDirection(sweep)) => PathParameter::Arc(radius, arc, SweepDirection(!sweep)),
+                other => other,
+            });
+        }
+        result
+    }
+    //straight lines only, one list of points for each move
+    pub fn flatten(&self, tolerance:f32) -> Vec<Vec<SvgPosition>> {
+        let mut result: Vec<Vec<SvgPosition>> = Vec::new();
+        for (pos, param) in self.positions.iter().zip(&self.parameters) {
+            let Some(line) = result.last_mut().filter(|_| !matches!(param, PathParameter::Move)) else {
+                result.push(vec![*pos]);
+                continue
+            };
+            match *param {
+                PathParameter::Arc(radius, arc, sweep) => {
+                    let from = *line.last().expect("lines start with a point");
+                    line.extend(flatten_arc(from, *pos, radius, arc, sweep, tolerance));
                 }
+                _ => line.push(*pos),
             }
         }
-        data
+        result
     }
     pub fn build_data(self) -> Data {
-        let mut data = Data::new();
+        self.apphend(Data::new())
+    }
+    pub fn apphend(self, mut data:Data) -> Data {
         for (pos, param) in self.positions.into_iter().zip(self.parameters) {
             data = match param {
                 PathParameter::Move => {
                     data.add(Move(A,(pos.0,pos.1).into()))
                 }
+                PathParameter::Line => {
+                    data.add(Line(A,(pos.0,pos.1).into()))
+                }
                 PathParameter::Arc(radius,LargeArcFlag(arc),SweepDirection(sweep)) => {
                     data.add(EllipticalArc(A, (
                         radius,radius,
@@ -103,6 +230,12 @@ impl PathBuilder {
     }
 }
 
+impl From<(f32,f32)> for SvgPosition {
+    fn from(value: (f32,f32)) -> Self {
+        SvgPosition(value.0, value.1)
+    }
+}
+
 #[macro_export] 
 macro_rules! pord_vec2dot {
     ($pord_vec:expr, $dist_mod:expr, $radius:expr, $ctx:expr, $doc:expr) => {
@@ -124,4 +257,73 @@ macro_rules! pord_from_vec_pop {
         loc.add_dist($dist_mod);
         let $pord_name = Rc::new(gallifreyan::pord::PordOrCord::Pord(loc));
     };
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START :SvgPosition = SvgPosition(10.0, 0.0);
+    const END :SvgPosition = SvgPosition(0.0, 10.0);
+
+    //how far round the centre the points go, positive is svg's sweep direction
+    fn turned(centre:SvgPosition, points:&[SvgPosition]) -> f32 {
+        let angle = |p:&SvgPosition| (p.1 - centre.1).atan2(p.0 - centre.0);
+        points.windows(2).map(|pair| {
+            let step = angle(&pair[1]) - angle(&pair[0]);
+            (step + PI).rem_euclid(2.0*PI) - PI
+        }).sum()
+    }
+
+    fn check(arc:bool, sweep:bool, centre:SvgPosition, span:f32) {
+        let tolerance = 0.01;
+        let points = flatten_arc(START, END, 10.0, LargeArcFlag(arc), SweepDirection(sweep), tolerance);
+        assert_eq!(points.last(), Some(&END));
+        let all: Vec<SvgPosition> = std::iter::once(START).chain(points).collect();
+        for p in &all {
+            assert!(((p.0 - centre.0).hypot(p.1 - centre.1) - 10.0).abs() < 1e-3, "{:?} is off the circle round {:?}", p, centre);
+        }
+        assert!((turned(centre, &all) - span).abs() < 1e-3, "large {} sweep {} went {} round", arc, sweep, turned(centre, &all));
+        //the middle of each chord is as far as it gets from the curve
+        for pair in all.windows(2) {
+            let half = (pair[1].0 - pair[0].0).hypot(pair[1].1 - pair[0].1)/2.0;
+            let sagitta = 10.0 - (100.0 - half*half).sqrt();
+            assert!(sagitta <= tolerance*1.01, "chord {:?} is {} off", pair, sagitta);
+        }
+    }
+
+    #[test]
+    fn arc_flags() {
+        check(false, true, SvgPosition(0.0, 0.0), PI/2.0);
+        check(true, true, SvgPosition(10.0, 10.0), 3.0*PI/2.0);
+        check(false, false, SvgPosition(10.0, 10.0), -PI/2.0);
+        check(true, false, SvgPosition(0.0, 0.0), -3.0*PI/2.0);
+    }
+
+    #[test]
+    fn tolerance_sets_the_points() {
+        let points = |tolerance:f32| flatten_arc(START, END, 10.0, LargeArcFlag(false), SweepDirection(true), tolerance).len();
+        assert!(points(0.001) > points(0.1));
+        //a tolerance bigger than the arc still gets there
+        assert_eq!(points(100.0), 1);
+        //0 can't be met, so it stops at the cap rather than running forever
+        assert_eq!(points(0.0), MAX_ARC_POINTS);
+        //so do ends that are on top of each other
+        assert!(flatten_arc(START, START, 10.0, LargeArcFlag(true), SweepDirection(true), 0.1).is_empty());
+    }
+
+    #[test]
+    fn flatten_splits_at_moves() {
+        let mut path = PathBuilder::new();
+        path.move_to(SvgPosition(0.0, 0.0));
+        path.line_to(START);
+        path.arc_to(END, 10.0, LargeArcFlag(false), SweepDirection(true));
+        path.move_to(SvgPosition(20.0, 20.0));
+        path.line_to(SvgPosition(30.0, 20.0));
+        let lines = path.flatten(0.01);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(&lines[0][..2], &[SvgPosition(0.0, 0.0), START]);
+        assert_eq!(lines[0].last(), Some(&END));
+        assert!(lines[0].len() > 3);
+        assert_eq!(lines[1], vec![SvgPosition(20.0, 20.0), SvgPosition(30.0, 20.0)]);
+    }
+}
@@ -0,0 +1,118 @@
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+use svg::Document;
+
+use crate::basic;
+use crate::bounds::{BBox, Bounded};
+use crate::ctx::Context;
+use crate::decorator::{Linebuilder, StraightLine};
+use crate::pord::{Cartesian, POrd, PordOrCord};
+use crate::translate::VowelKind;
+
+//how far the i & u lines stick out, in vowel radii
+const VOWEL_LINE_RATIO :f32 = 2.5;
+//clearance between a vowel & the word line, in stroke widths
+const VOWEL_GAP_RATIO :f32 = 1.0;
+
+//where a vowel sits relative to the word line, or its consonant
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum VowelPlacement {OnLine, Outside, Inside}
+//which way the i & u lines point, relative to the word centre
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum VowelLine {Inward, Outward}
+#[derive(Debug, Clone)]
+pub struct Vowel {
+    kind:VowelKind,
+    pord:Rc<PordOrCord>,
+    radius:f32,
+    ctx:Option<Context>,
+}
+
+impl Vowel {
+    pub fn new(kind:VowelKind, pord:Rc<PordOrCord>, radius:f32, ctx:Option<Context>) -> Vowel {
+        Vowel { kind, pord, radius, ctx }
+    }
+    pub fn kind(&self) -> VowelKind {
+        self.kind
+    }
+    pub fn pord(&self) -> Rc<PordOrCord> {
+        self.pord.clone()
+    }
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+    pub fn ctx(&self) -> Option<&Context> {
+        self.ctx.as_ref()
+    }
+    pub fn placement(&self) -> VowelPlacement {
+        placement(self.kind)
+    }
+    pub fn line(&self) -> Option<VowelLine> {
+        match self.kind {
+            VowelKind::I => Some(VowelLine::Inward),
+            VowelKind::U => Some(VowelLine::Outward),
+            _ => None,
+        }
+    }
+    pub fn draw(&self, mut doc:Document, word:&PordOrCord, default_ctx:&Context) -> Document {
+        let ctx = self.ctx.as_ref().unwrap_or(default_ctx);
+        doc = basic::circle(doc, self.pord.as_ref(), self.radius*self.pord.frame().1, ctx);
+        match self.line_node(word, ctx) {
+            Some(line) => line.draw(doc),
+            None => doc,
+        }
+    }
+    //the circle & the i or u line, if it has one
+    pub fn bounding_box(&self, word:&PordOrCord, default_ctx:&Context) -> BBox {
+        let ctx = self.ctx.as_ref().unwrap_or(default_ctx);
+        let circle = basic::circle_bounds(self.pord.as_ref(), self.radius*self.pord.frame().1, ctx);
+        match self.line_node(word, ctx) {
+            Some(line) => circle.union(&line.bounding_box()),
+            None => circle,
+        }
+    }
+    fn line_node(&self, word:&PordOrCord, ctx:&Context) -> Option<StraightLine> {
+        let outward = self.pord.angle_to(word) + PI;
+        let theta = match self.line()? {
+            VowelLine::Inward => outward + PI,
+            VowelLine::Outward => outward,
+        };
+        let mut builder = Linebuilder::new(ctx);
+        _ = builder.add_pord(Rc::new(PordOrCord::Pord(POrd::new(self.radius, theta, self.pord.clone()))));
+        _ = builder.add_pord(Rc::new(PordOrCord::Pord(POrd::new(self.radius*(1.0 + VOWEL_LINE_RATIO), theta, self.pord.clone()))));
+        Some(builder.try_into().expect("both ends were just added"))
+    }
+}
+
+pub fn placement(kind:VowelKind) -> VowelPlacement {
+    match kind {
+        VowelKind::A => VowelPlacement::Outside,
+        VowelKind::O => VowelPlacement::Inside,
+        VowelKind::E | VowelKind::I | VowelKind::U => VowelPlacement::OnLine,
+    }
+}
+
+//distance from the word centre for a vowel that isn't attached to a consonant
+pub(crate) fn standalone_dist(kind:VowelKind, word_radius:f32, radius:f32, stroke:f32) -> f32 {
+    let clearance = stroke/2.0 + VOWEL_GAP_RATIO*stroke + radius;
+    match placement(kind) {
+        VowelPlacement::OnLine => word_radius,
+        VowelPlacement::Outside => word_radius + clearance,
+        VowelPlacement::Inside => word_radius - clearance,
+    }
+}
+
+//a goes outside the word line beside its consonant, o on the consonant's edge nearest the word centre,
+//e, i & u on the consonant's centre
+pub(crate) fn attached_pord(kind:VowelKind, host:&Rc<PordOrCord>, host_radius:f32, word:&Rc<PordOrCord>, word_radius:f32, radius:f32, stroke:f32) -> Rc<PordOrCord> {
+    let outward = word.angle_to(host.as_ref());
+    match placement(kind) {
+        VowelPlacement::Outside => {
+            let dist = standalone_dist(kind, word_radius, radius, stroke);
+            Rc::new(PordOrCord::Pord(POrd::new(dist, outward, word.clone())))
+        }
+        VowelPlacement::Inside => Rc::new(PordOrCord::Pord(POrd::new(host_radius, host.angle_to(word.as_ref()), host.clone()))),
+        VowelPlacement::OnLine => host.clone(),
+    }
+}
@@ -1,42 +1,50 @@
-use std::f32::consts::PI;
+use std::f32::consts::{FRAC_PI_2, PI};
 use std::rc::{Rc, Weak};
 
 use svg::Document;
-use svg::node::element::{Circle, Path};
+use svg::node::element::{Circle, Group, Path};
+use svg::node::element::path::Data;
 
+use crate::basic;
+use crate::bounds::{BBox, Bounded};
 use crate::ctx::Context;
+use crate::decorator::{self, Linebuilder, StraightLine};
+use crate::error::{self, GallifreyanError};
+use crate::geometry::{self, Disc, LetterThi};
 use crate::pord::{Cartesian, POrd, PordOrCord};
+use crate::translate::VowelKind;
 use crate::utils;
 use crate::utils::{LargeArcFlag, PathBuilder, SvgPosition, SweepDirection};
-use crate::StemType;
+use crate::vowel::{self, Vowel};
+use crate::{Decoration, StemType};
 
 const SORT_PRECISION :i32 = 1000;
-const B_DIVOT_FUDGE_PRECISION :f32 = 0.2;
 
-enum RadiusType{Inner,Average,Outer}
+pub enum RadiusType{Inner,Outer}
 #[derive(Debug,Clone, Copy,PartialEq, PartialOrd)]
 pub struct InnerAngle(f32);
 #[derive(Debug,Clone, Copy,PartialEq, PartialOrd)]
 pub struct OuterAngle(f32);
-const ZERO_ANGLE : (InnerAngle, OuterAngle) = (InnerAngle(0.0),OuterAngle(0.0));
-#[derive(Debug, Clone)]
-enum CircleOrClosedPath {
-    Cir(Circle),
-    Closed(Path)
-}
 #[derive(Debug, Clone)]
 pub struct LetterArc {
     pord: Rc<PordOrCord>,
     radius:f32,
     stem_type:StemType,
     ctx:Option<Context>,
+    vowel:Option<Vowel>,
+    decoration:Decoration,
+    //what it was translated from, for the svg's data-letter
+    text:Option<String>,
 }
 #[derive(Debug, Clone)]
 pub struct WordCircle {
     name:String,
+    id:Option<String>,
+    transliteration:Option<String>,
     pord:Rc<PordOrCord>,
     radius:f32,
     arcs: Vec<LetterArc>,
+    vowels: Vec<Vowel>,
     default_ctx:Context,
     path_circle: bool,
     sorted: bool,
@@ -44,9 +52,12 @@ pub struct WordCircle {
 #[derive(Debug, Clone)]
 pub struct WordArc {
     name:String,
+    id:Option<String>,
+    transliteration:Option<String>,
     pord:Rc<PordOrCord>,
     radius:f32,
     arcs: Vec<LetterArc>,
+    vowels: Vec<Vowel>,
     default_ctx:Context,
     start_angle:f32,
     end_angle:f32,
@@ -55,15 +66,37 @@ pub struct WordArc {
 }
 
 pub trait Word:Cartesian {
+    fn name(&self) -> &str;
+    //the svg id of the word's group, made from the name unless it's been set
+    fn id(&self) -> String;
+    fn set_id(&mut self, id:&str);
+    //the letters it's written with, see translate::transliterate
+    fn transliteration(&self) -> Option<&str>;
+    fn set_transliteration(&mut self, transliteration:&str);
+    //what screen readers say for the word, the name & how it's spelt in gallifreyan
+    fn aria_label(&self) -> String {
+        match self.transliteration() {
+            Some(letters) => format!("{} ({})", self.name(), letters),
+            None => self.name().to_string(),
+        }
+    }
     fn pord(&self) -> Rc<PordOrCord>;
     fn radius(&self) -> f32;
     fn arcs(&mut self) -> &mut Vec<LetterArc>;
+    //vowels that aren't attached to a consonant
+    fn vowels(&mut self) -> &mut Vec<Vowel>;
+    //the same two, for looking without changing anything
+    fn letter_arcs(&self) -> &[LetterArc];
+    fn loose_vowels(&self) -> &[Vowel];
     fn ctx(&self) -> Context;
     fn get_last_letter(&self) -> Option<&LetterArc>;
     fn get_first_letter(&self) -> Option<&LetterArc>;
     fn default_word_start_angle(&self) -> f32;
     fn default_word_end_angle(&self) -> f32;
     fn new_letter(&mut self, pord:Rc<PordOrCord>,radius:f32,stem_type:StemType,ctx:Option<Context>) -> Weak<PordOrCord>;
+    fn try_new_letter(&mut self, pord:Rc<PordOrCord>,radius:f32,stem_type:StemType,ctx:Option<Context>) -> Result<Weak<PordOrCord>, GallifreyanError> {
+        Ok(self.new_letter(pord, radius, stem_type, ctx))
+    }
     fn new_letter_from_data(&mut self, r:f32,theta:f32,radius:f32,stem_type:StemType,ctx:Option<Context>) -> Rc<PordOrCord> {
         let dist = if stem_type == StemType::S {
             r + self.ctx().stroke().strokewidth()/2.0
@@ -86,10 +119,148 @@ pub trait Word:Cartesian {
         let result = utils::generate_pord_vector(num_of_attach,letter_pord.clone(),radius);
         (letter_pord,result)
     }
+    //a vowel on its own, on, outside or inside the word line at theta
+    fn new_vowel(&mut self, kind:VowelKind, theta:f32, radius:f32, ctx:Option<Context>) -> Rc<PordOrCord> {
+        let dist = vowel::standalone_dist(kind, self.radius(), radius, self.ctx().stroke().strokewidth());
+        let location = Rc::new(PordOrCord::Pord(POrd::new(dist, theta, self.pord())));
+        self.vowels().push(Vowel::new(kind, location.clone(), radius, ctx));
+        location
+    }
+    //puts the vowel where it goes for the consonant at letter, the last one added if several share it
+    fn attach_vowel(&mut self, letter:&Rc<PordOrCord>, kind:VowelKind, radius:f32, ctx:Option<Context>) -> Result<Rc<PordOrCord>, GallifreyanError> {
+        let (word, word_radius, stroke) = (self.pord(), self.radius(), self.ctx().stroke().strokewidth());
+        let host_radius = self.letter_mut(letter)?.radius;
+        let location = vowel::attached_pord(kind, letter, host_radius, &word, word_radius, radius, stroke);
+        self.letter_mut(letter)?.set_vowel(Vowel::new(kind, location.clone(), radius, ctx));
+        Ok(location)
+    }
+    fn decorate(&mut self, letter:&Rc<PordOrCord>, decoration:Decoration) -> Result<(), GallifreyanError> {
+        self.letter_mut(letter)?.set_decoration(decoration);
+        Ok(())
+    }
+    fn set_letter_text(&mut self, letter:&Rc<PordOrCord>, text:&str) -> Result<(), GallifreyanError> {
+        self.letter_mut(letter)?.set_text(text);
+        Ok(())
+    }
+    //the last letter added at that pord, if several share it
+    fn letter_mut(&mut self, letter:&Rc<PordOrCord>) -> Result<&mut LetterArc, GallifreyanError> {
+        let name = self.name().to_string();
+        self.arcs().iter_mut().rev()
+            .find(|arc| Rc::ptr_eq(&arc.pord, letter))
+            .ok_or(GallifreyanError::NoSuchLetter(name))
+    }
+    //the lines of every decorated letter, ending just past the word line
+    fn line_stubs(&mut self) -> Vec<StraightLine> {
+        let arcs = self.arcs().clone();
+        arcs.iter().flat_map(|letter| self.letter_line_stubs(letter)).collect()
+    }
+    fn letter_line_stubs(&self, letter:&LetterArc) -> Vec<StraightLine> {
+        let Decoration::Lines(num) = letter.decoration else {
+            return Vec::new()
+        };
+        let (word, word_ctx) = (self.pord(), self.ctx());
+        let (_, outer_radius) = self.get_radii();
+        let ctx = letter.ctx.as_ref().unwrap_or(&word_ctx);
+        let line_ctx = ctx.new_strokewidth(ctx.stroke().strokewidth()/2.0);
+        let open = matches!(letter.stem_type, StemType::B | StemType::S);
+        decorator::letter_line_stubs(&word, outer_radius, &letter.pord, letter.radius, open, num, letter.crowded())
+            .into_iter()
+            .map(|(start, end)| {
+                let mut builder = Linebuilder::new(&line_ctx);
+                _ = builder.add_pord(start);
+                _ = builder.add_pord(end);
+                builder.try_into().expect("both ends were just added")
+            })
+            .collect()
+    }
+    //for when something else joins the lines up, so the word stops drawing them itself
+    fn take_line_stubs(&mut self) -> Vec<StraightLine> {
+        let stubs = self.line_stubs();
+        for letter in self.arcs().iter_mut() {
+            if let Decoration::Lines(_) = letter.decoration {
+                letter.decoration = Decoration::None;
+            }
+        }
+        stubs
+    }
+    //every letter gets a group of its own, holding its circle if it's drawn on top, decorations & vowel
+    fn draw_letters(&mut self, mut doc:Document, circled:&[bool]) -> Document {
+        let (word, word_ctx, id) = (self.pord(), self.ctx(), self.id());
+        let arcs = self.arcs().clone();
+        for (i, letter) in arcs.iter().enumerate() {
+            let mark = doc.get_children().len();
+            if circled[i] {
+                doc = doc.add(self.letter_circle_node(letter));
+            }
+            if let Decoration::Dots(num) = letter.decoration {
+                let ctx = letter.ctx.as_ref().unwrap_or(&word_ctx).new_filled();
+                let (dots, radius) = decorator::letter_dots(&word, &letter.pord, letter.radius, num, letter.crowded());
+                for dot in dots {
+                    doc = basic::circle(doc, dot.as_ref(), radius, &ctx);
+                }
+            }
+            for line in self.letter_line_stubs(letter) {
+                doc = line.draw(doc);
+            }
+            let mut group = Group::new()
+                .set("id", letter_id(&id, i))
+                .set("class", "letter");
+            if let Some(text) = &letter.text {
+                group = group.set("data-letter", text.as_str());
+            }
+            if let Some(vowel) = &letter.vowel {
+                doc = vowel.draw(doc, &word, &word_ctx);
+                group = group.set("data-vowel", vowel_letter(vowel.kind()));
+            }
+            doc = basic::group_since(doc, mark, group);
+        }
+        let vowels = self.vowels().clone();
+        for (i, vowel) in vowels.iter().enumerate() {
+            let mark = doc.get_children().len();
+            doc = vowel.draw(doc, &word, &word_ctx);
+            let group = Group::new()
+                .set("id", vowel_id(&id, i))
+                .set("class", "vowel")
+                .set("data-letter", vowel_letter(vowel.kind()));
+            doc = basic::group_since(doc, mark, group);
+        }
+        doc
+    }
+    //every id the word writes into the svg, its own first
+    fn svg_ids(&mut self) -> Vec<String> {
+        let id = self.id();
+        let (letters, vowels) = (self.arcs().len(), self.vowels().len());
+        [id.clone(), format!("{}-ring", id)].into_iter()
+            .chain((0..letters).map(|i| letter_id(&id, i)))
+            .chain((0..vowels).map(|i| vowel_id(&id, i)))
+            .collect()
+    }
+    //the id the letter at that pord is drawn with, which needs the letters in order first
+    fn letter_svg_id(&mut self, letter:&Rc<PordOrCord>) -> Option<String> {
+        self.try_sort_letters().ok()?;
+        let id = self.id();
+        self.arcs().iter().rposition(|arc| Rc::ptr_eq(&arc.pord, letter)).map(|i| letter_id(&id, i))
+    }
+    //wraps whatever the word drew since `mark` in its own group
+    fn group_word(&self, doc:Document, mark:usize) -> Document {
+        let group = Group::new()
+            .set("id", self.id())
+            .set("class", "word")
+            .set("data-word", self.name())
+            .set("role", "img")
+            .set("aria-label", self.aria_label());
+        basic::group_since(doc, mark, group)
+    }
     fn sorted(&mut self) -> &mut bool;
     fn sort_letters(&mut self) {
-        if *self.sorted() {
-            return
+        if let Err(err) = self.try_sort_letters() {
+            println!("Sorting failed, continuously looping.");
+            panic!("{}", err)
+        }
+    }
+    fn try_sort_letters(&mut self) -> Result<(), GallifreyanError> {
+        if *self.sorted() || self.arcs().is_empty() {
+            return Ok(())
         } 
         let location= self.pord();
         self.arcs().sort_by_key(|a|location.angle_to(a.pord.as_ref()) as i32);
@@ -99,8 +270,7 @@ pub trait Word:Cartesian {
         let mut count = self.arcs().len();
         while let Some(last) = self.arcs().last() {
             if count == 0 {
-                println!("Sorting failed, continuously looping.");
-                panic!()
+                return Err(GallifreyanError::SortDiverged(self.name().to_string()))
             }
             count -= 1;
             if location.angle_to(last.pord().as_ref()) < overlap {
@@ -110,18 +280,116 @@ pub trait Word:Cartesian {
             self.arcs().insert(1,last);
         }
         *self.sorted() = true;
+        Ok(())
+    }
+    //whether the letters cut into the word line, rather than just sitting on top
+    fn draws_path(&self) -> bool;
+    //whether the word line goes all the way round
+    fn closed(&self) -> bool;
+    fn try_draw(mut self, doc:Document) -> Result<Document, GallifreyanError> where Self:Sized {
+        self.check_drawable()?;
+        Ok(self.draw(doc))
+    }
+    fn check_drawable(&mut self) -> Result<(), GallifreyanError> {
+        self.ctx().try_origin()?;
+        error::all_anchors(self.anchor_faults())?;
+        for letter in self.arcs().iter() {
+            if let Some(ctx) = &letter.ctx {
+                ctx.try_origin()?;
+            }
+        }
+        let mut vowels: Vec<Vowel> = self.arcs().iter().filter_map(|letter| letter.vowel.clone()).collect();
+        vowels.extend(self.vowels().iter().cloned());
+        for ctx in vowels.iter().filter_map(|vowel| vowel.ctx()) {
+            ctx.try_origin()?;
+        }
+        if !self.draws_path() {
+            return Ok(())
+        }
+        if self.arcs().is_empty() {
+            return Err(GallifreyanError::EmptyWord(self.name().to_string()))
+        }
+        self.try_sort_letters()?;
+        for (i, letter) in self.arcs().clone().iter().enumerate() {
+            if !self.crosses_line(letter) {
+                return Err(GallifreyanError::GeometryUnsolvable(format!("letter {} of {} doesn't cross the word line", i, self.name())))
+            }
+        }
+        //the line follows the first of a stack in & the last out, so b & s letters have to grow outwards
+        let arcs = self.arcs().clone();
+        let mut i = 0;
+        for stack in stacks(&arcs) {
+            let divots: Vec<&LetterArc> = stack.iter().filter(|letter| cuts_line(letter)).collect();
+            if divots.windows(2).any(|pair| pair[0].radius >= pair[1].radius) {
+                return Err(GallifreyanError::GeometryUnsolvable(format!("letters stacked on letter {} of {} don't get bigger going out", i, self.name())))
+            }
+            i += stack.len();
+        }
+        Ok(())
+    }
+    //J letters sit inside so they don't have to
+    fn crosses_line(&self, letter:&LetterArc) -> bool {
+        match letter.stem_type {
+            StemType::B | StemType::S => matches!(self.calc_letter_thi(letter), LetterThi{outer:Some(_), inner:Some(_), ..}),
+            StemType::Z => matches!(self.calc_letter_thi(letter), LetterThi{inner_boundary:Some(_), outer_boundary:Some(_), ..}),
+            StemType::J => true,
+        }
+    }
+    //every pord the word is drawn from, with what it is for error messages
+    fn named_pords(&self) -> Vec<(String, Rc<PordOrCord>)> {
+        let name = self.name().to_string();
+        let mut pords = vec![(name.clone(), self.pord())];
+        for (i, letter) in self.letter_arcs().iter().enumerate() {
+            pords.push((format!("letter {} of {}", i, name), letter.pord()));
+            if let Some(vowel) = &letter.vowel {
+                pords.push((format!("the vowel on letter {} of {}", i, name), vowel.pord()));
+            }
+        }
+        for (i, vowel) in self.loose_vowels().iter().enumerate() {
+            pords.push((format!("vowel {} of {}", i, name), vowel.pord()));
+        }
+        pords
+    }
+    //one for every part of the word hanging off a dropped anchor
+    fn anchor_faults(&self) -> Vec<GallifreyanError> {
+        self.named_pords().iter().filter_map(|(owner, pord)| pord.check_anchors(owner).err()).collect()
+    }
+    //draw carries on past dropped anchors with the svg origin standing in, but says which ones
+    fn report_anchors(&self) {
+        for fault in self.anchor_faults() {
+            println!("{}. Using svg origin instead.", fault);
+        }
     }
     fn start_path_data(&self, angle:(InnerAngle,OuterAngle)) -> (PathBuilder, PathBuilder);
-    fn end_path_data(&self, doc:Document, data:(PathBuilder, PathBuilder)) -> Document;
+    fn end_path_data(&self, doc:Document, data:(PathBuilder, PathBuilder), gaps:PathBuilder) -> Document;
+    //the word line is filled between its edges, or with centre lines both edges are the same so one gets stroked
+    fn ring_node(&self, data:Data) -> Path {
+        let ctx = self.ctx();
+        let ring = Path::new()
+            .set("id", format!("{}-ring", self.id()))
+            .set("d", data);
+        if ctx.centre_lines() {
+            ring.set("fill", "none")
+                .set("stroke", ctx.colour().stroke())
+                .set("stroke-width", ctx.stroke().strokewidth())
+        } else {
+            ring.set("fill", ctx.colour().stroke())
+                .set("fill-rule", "evenodd")
+                .set("stroke", "none")
+                .set("stroke-width", 0.0)
+        }
+    }
     fn draw(self,doc:Document) -> Document;
     //This assumes the arc is already sorted.
     fn word_arc_loop(&mut self, mut doc:Document) -> Document {
         let arc_vec = self.arcs().clone();
-        let mut l_iter = arc_vec.iter();
-        let letter = l_iter.next().expect("no letters in word arc");
-        let mut prev_pord = letter.pord();
-        let mut circle_letters = Vec::new();
-        let (mut i_letter_start_angle, mut o_letter_start_angle) = self.calc_starting_letter_angle();
+        let stacks = stacks(&arc_vec);
+        let closed = self.closed() && stacks.len() > 1;
+        let mut s_iter = stacks.into_iter();
+        let stack = s_iter.next().expect("no letters in word arc");
+        let mut circled = Vec::new();
+        let mut gaps = PathBuilder::new();
+        let (i_letter_start_angle, o_letter_start_angle) = self.stack_start_angle(stack);
         let i_word_start_angle = if i_letter_start_angle.0 < self.default_word_start_angle() {
             i_letter_start_angle
         } else {self.default_word_start_angle().into()};
@@ -132,53 +400,37 @@ pub trait Word:Cartesian {
         if i_word_start_angle < i_letter_start_angle || o_word_start_angle < o_letter_start_angle {
             data = self.draw_word_arc(data,(i_word_start_angle,o_word_start_angle),(i_letter_start_angle,o_letter_start_angle));
         }
-        let mut cir: Option<CircleOrClosedPath>;
+        let first_stack = stack;
+        //steps before the first letter, in case the last letter wraps round into it
+        let lead = (data.0.len(), data.1.len());
+        let mut prev_stack = stack;
         let mut end_angle: (InnerAngle,OuterAngle);
-        (cir, data, end_angle) = self.draw_letter_arc(letter, data);
-        if let Some(letter_circle) = cir {
-            circle_letters.push(letter_circle);
-        }; 
-        while let Some(letter) = l_iter.next() {
-            let mut skip = false;
-            if Rc::ptr_eq(&prev_pord, &letter.pord()) {
-                skip = true;
-            } else if let Some(anchor) = letter.pord().get_anchor() {
-                if Weak::ptr_eq(&Rc::downgrade(&prev_pord), &anchor) {
-                    skip = true;
-                }
+        (data, end_angle) = self.draw_stack(stack, data, &mut gaps, &mut circled);
+        for stack in s_iter {
+            let (mut i_letter_start_angle, mut o_letter_start_angle) = self.stack_start_angle(stack);
+            let meet = self.stack_crossings(prev_stack, stack);
+            //no word line between letters that meet
+            if meet.0.is_some() {
+                i_letter_start_angle = end_angle.0;
             }
-            if skip {
-                prev_pord = letter.pord();
-                //first letter must be innermost
-                (cir, data,_) = self.draw_stacked_letter_arc(letter, data);
-                if let Some(letter_circle) =  cir {
-                    circle_letters.push(letter_circle);
-                };
-                continue;
+            if meet.1.is_some() {
+                o_letter_start_angle = end_angle.1;
             }
-            prev_pord = letter.pord();
-            i_letter_start_angle.0 = self.angle_to(letter.pord.as_ref());
-            o_letter_start_angle.0 = i_letter_start_angle.0;
-            let (i_thi, o_thi) = match letter.stem_type {
-                StemType::J | StemType::Z => (0.0, 0.0), 
-                StemType::B | StemType::S => {
-                    if let (Some(thi1),Some(thi2),_,_,_) = self.calc_letter_thi(letter) {
-                        (thi2, thi1)
-                    } else {(0.0,0.0)}
-                }
-            };
-            i_letter_start_angle.0 -= i_thi;
-            o_letter_start_angle.0 -= o_thi;
-            if i_letter_start_angle > end_angle.0 {
-                //this will break if we start doing overlapping s_divots
-                data = self.draw_word_arc(data,end_angle,(i_letter_start_angle,o_letter_start_angle));
+            data = self.draw_word_arc(data,end_angle,(i_letter_start_angle,o_letter_start_angle));
+            (data, end_angle) = self.draw_stack(stack, data, &mut gaps, &mut circled);
+            let ((prev_i, prev_o), (next_i, next_o)) = (stack_bounds(prev_stack), stack_bounds(stack));
+            if let Some(meet) = meet.0 {
+                self.merge_letter_arcs(&mut data.0, prev_i, next_i, meet);
+            }
+            if let Some(meet) = meet.1 {
+                self.merge_letter_arcs(&mut data.1, prev_o, next_o, meet);
             }
-            (cir, data,end_angle) = self.draw_letter_arc(letter, data);
-            if let Some(letter_circle) =  cir {
-                circle_letters.push(letter_circle);
-            };
+            prev_stack = stack;
         }
-        let ending_angle = (
+        let wrap = if closed {
+            self.stack_crossings(prev_stack, first_stack)
+        } else {(None, None)};
+        let mut ending_angle: (InnerAngle,OuterAngle) = (
             if i_word_start_angle.0 < self.default_word_start_angle() {
                 i_word_start_angle.0 + self.default_word_end_angle()
             } else {self.default_word_end_angle()}.into(),
@@ -186,106 +438,161 @@ pub trait Word:Cartesian {
                 o_word_start_angle.0 + self.default_word_end_angle()
             } else {self.default_word_end_angle()}.into()
         );
+        if wrap.0.is_some() {
+            ending_angle.0 = end_angle.0;
+        }
+        if wrap.1.is_some() {
+            ending_angle.1 = end_angle.1;
+        }
         data = self.draw_word_arc(data,end_angle,ending_angle);
-        doc = self.end_path_data(doc, data);
-        for node in circle_letters {
-            doc = match node {
-                CircleOrClosedPath::Cir(cir) => doc.add(cir),
-                CircleOrClosedPath::Closed(path) => doc.add(path),
-            };
+        let ((last_i, last_o), (first_i, first_o)) = (stack_bounds(prev_stack), stack_bounds(first_stack));
+        if let Some(meet) = wrap.0 {
+            self.wrap_letter_arcs(&mut data.0, last_i, first_i, lead.0, meet);
         }
-        doc
+        if let Some(meet) = wrap.1 {
+            self.wrap_letter_arcs(&mut data.1, last_o, first_o, lead.1, meet);
+        }
+        doc = self.end_path_data(doc, data, gaps);
+        self.draw_letters(doc, &circled)
     }
+    //boundaries with nowhere to go are left alone, their letters meet there
     fn draw_word_arc(&self, mut data:(PathBuilder, PathBuilder), start_angle:(InnerAngle,OuterAngle), end_angle:(InnerAngle,OuterAngle)) -> (PathBuilder, PathBuilder) {
         let (i_radius,o_radius) = self.get_radii();
-        let i_end = self.calc_word_arc_svg_point(end_angle.0.0, RadiusType::Inner);
-        let o_end = self.calc_word_arc_svg_point(end_angle.1.0, RadiusType::Outer);
-        data.1.arc_to(
-            o_end,
-            o_radius,
-            LargeArcFlag(end_angle.1.0 - start_angle.1.0 > PI),
-            SweepDirection(false) //sweep dir - 0 anti-clockwise
-        );
-        data.0.arc_to(
-            i_end,
-            i_radius,
-            LargeArcFlag(end_angle.0.0 - start_angle.0.0 > PI),
-            SweepDirection(false), //sweep dir - 0 anti-clockwise
-        );
+        if end_angle.1 > start_angle.1 {
+            data.1.arc_to(
+                self.calc_word_arc_svg_point(end_angle.1.0, RadiusType::Outer),
+                o_radius,
+                LargeArcFlag(end_angle.1.0 - start_angle.1.0 > PI),
+                SweepDirection(false) //sweep dir - 0 anti-clockwise
+            );
+        }
+        if end_angle.0 > start_angle.0 {
+            data.0.arc_to(
+                self.calc_word_arc_svg_point(end_angle.0.0, RadiusType::Inner),
+                i_radius,
+                LargeArcFlag(end_angle.0.0 - start_angle.0.0 > PI),
+                SweepDirection(false), //sweep dir - 0 anti-clockwise
+            );
+        }
         data
     }
-    fn draw_stacked_letter_arc(&self, letter:&LetterArc, mut data:(PathBuilder, PathBuilder)) -> (Option<CircleOrClosedPath>,(PathBuilder, PathBuilder), (InnerAngle,OuterAngle)) {
-        let mut inner_path_end_angle = self.angle_to(letter.pord.as_ref());
-        let b_divot = match letter.stem_type {
-            StemType::J | StemType::Z => {
-                return (Some(CircleOrClosedPath::Cir(self.letter_circle_node(letter))),data,(inner_path_end_angle.into(),inner_path_end_angle.into())); 
-            }, 
-            StemType::S => false,
-            StemType::B => true
+    //neighbouring letters whose cut outs overlap meet where their circles cross, instead of on the word line.
+    //expects the path to end with prev's arc then next's
+    fn merge_letter_arcs(&self, path:&mut PathBuilder, prev:&LetterArc, next:&LetterArc, meet:SvgPosition) {
+        let (Some(next_step), Some((_, prev_param))) = (path.pop(), path.pop()) else {
+            return
         };
-        let mut outer_path_end_angle = inner_path_end_angle;
-        let (mut outer_path_start_angle, mut inner_path_start_angle) = (outer_path_end_angle,inner_path_end_angle); 
-        let mut out_large_arc = true;
-        if let (_,Some(thi2),Some(thi1),_,Some(theta)) = self.calc_letter_thi(letter) {
-            outer_path_end_angle += thi1;
-            outer_path_start_angle -= thi1;
-            inner_path_end_angle += thi2;
-            inner_path_start_angle -= thi2;
-            if theta < PI/2.0 {
-                out_large_arc = false
-            }
-        }        
-        let point_1 = self.calc_word_arc_svg_point(inner_path_start_angle,RadiusType::Average);
-        let point_2 = self.calc_word_arc_svg_point(outer_path_start_angle,RadiusType::Average);
-        let point_3 = self.calc_word_arc_svg_point(outer_path_end_angle,RadiusType::Average);
-        let point_4 = self.calc_word_arc_svg_point(inner_path_end_angle,RadiusType::Average);
-        let (word_radius,_) = self.get_radii();
-        let (inner_letter_radius,outer_letter_radius)= self.get_letter_radii(letter);
-        let mut path_build = PathBuilder::new();
-        path_build.move_to(point_1);
-        path_build.arc_to(point_2, word_radius, LargeArcFlag(false), SweepDirection(false));
-        path_build.arc_to(point_3, inner_letter_radius + B_DIVOT_FUDGE_PRECISION/2.0, LargeArcFlag(b_divot && out_large_arc), SweepDirection(true));
-        path_build.arc_to(point_4, word_radius, LargeArcFlag(false), SweepDirection(false));
-        path_build.arc_to(point_1, outer_letter_radius + B_DIVOT_FUDGE_PRECISION, LargeArcFlag(b_divot && out_large_arc), SweepDirection(false));
-        let path = Path::new()
-            .set("d", path_build.build_data().close())
-            .set("fill", self.ctx().colour().stroke())
-            .set("stroke", "none")
-            .set("stroke-width", 0.0);
-        (Some(CircleOrClosedPath::Closed(path)),data,(inner_path_end_angle.into(),inner_path_end_angle.into()))
-    }
-    fn draw_letter_arc(&self, letter:&LetterArc, mut data:(PathBuilder, PathBuilder)) -> (Option<CircleOrClosedPath>,(PathBuilder, PathBuilder), (InnerAngle,OuterAngle)) {
-        let mut i_end_angle = self.angle_to(letter.pord.as_ref());
-        let b_divot = match letter.stem_type {
-            StemType::J | StemType::Z => {
-                return (Some(CircleOrClosedPath::Cir(self.letter_circle_node(letter))),data,(i_end_angle.into(),i_end_angle.into())); 
-            }, 
-            StemType::S => false,
-            StemType::B => true
+        let origin = self.ctx().origin();
+        path.push(meet, prev_param);
+        path.fit_large_arc(path.len() - 1, prev.pord.abs_svg_xy(origin).into());
+        path.push(next_step.0, next_step.1);
+        path.fit_large_arc(path.len() - 1, next.pord.abs_svg_xy(origin).into());
+    }
+    //same again for a closed word, where the last letter runs round into the first.
+    //the path restarts where they cross, dropping the `lead` steps that came before the first letter
+    fn wrap_letter_arcs(&self, path:&mut PathBuilder, last:&LetterArc, first:&LetterArc, lead:usize, meet:SvgPosition) {
+        let Some((_, last_param)) = path.pop() else {
+            return
         };
-        let (inner_letter_radius,outer_letter_radius)= self.get_letter_radii(letter);
-        let mut o_end_angle = i_end_angle;
-        let mut oversized_b = false;
-        if let (Some(thi1),Some(thi2),_,_,Some(theta)) = self.calc_letter_thi(letter) {
-            i_end_angle += thi2;
-            o_end_angle += thi1;
-            if b_divot && theta < PI/2.0 {
-                oversized_b = true;
-            }
+        let origin = self.ctx().origin();
+        path.push(meet, last_param);
+        path.fit_large_arc(path.len() - 1, last.pord.abs_svg_xy(origin).into());
+        path.restart(meet, lead);
+        path.fit_large_arc(1, first.pord.abs_svg_xy(origin).into());
+    }
+    //where neighbouring stacks meet, on the (inner, outer) boundaries
+    fn stack_crossings(&self, prev:&[LetterArc], next:&[LetterArc]) -> (Option<SvgPosition>,Option<SvgPosition>) {
+        let ((prev_i, prev_o), (next_i, next_o)) = (stack_bounds(prev), stack_bounds(next));
+        (self.letter_crossing(prev_i, next_i, true), self.letter_crossing(prev_o, next_o, false))
+    }
+    //where two letters' boundary circles cross, if that's on the word's side of the boundary
+    fn letter_crossing(&self, prev:&LetterArc, next:&LetterArc, inner:bool) -> Option<SvgPosition> {
+        let bulges = |letter:&LetterArc| match letter.stem_type {
+            StemType::B | StemType::S => Some(false),
+            StemType::Z => Some(true),
+            StemType::J => None,
+        };
+        let bulge = bulges(prev)?;
+        if bulges(next)? != bulge {
+            return None
         }
-        data.0.arc_to(
-            self.calc_word_arc_svg_point(i_end_angle,RadiusType::Inner),
-            outer_letter_radius,
-            LargeArcFlag(b_divot && !oversized_b), //large arc
-            SweepDirection(true), //sweep dir - 0 anti-clockwise
-        );
-        data.1.arc_to(
-            self.calc_word_arc_svg_point(o_end_angle,RadiusType::Outer),
-            inner_letter_radius,
-            LargeArcFlag(b_divot), //large arc
-            SweepDirection(true), //sweep dir - 0 anti-clockwise
-        );
-        (None, data, (i_end_angle.into(),o_end_angle.into()))
+        let origin = self.ctx().origin();
+        let radius = |letter:&LetterArc| {
+            let (lett_r_i, lett_r_o) = self.get_letter_radii(letter);
+            //divots cut the inner boundary with their outer edge, z bulges follow the matching edge
+            if inner == bulge {lett_r_i} else {lett_r_o}
+        };
+        let edge = |letter:&LetterArc| Disc::new(letter.pord.abs_svg_xy(origin).into(), radius(letter));
+        let crossing = edge(prev).crossings(&edge(next))?;
+        let (p1, p2) = (crossing.right, crossing.left);
+        let (x,y) = self.abs_svg_xy(origin);
+        let dist = |p:SvgPosition| (p.0 - x).hypot(p.1 - y);
+        //divots meet inside the word, bulges outside it
+        let meet = if (dist(p1) < dist(p2)) != bulge {p1} else {p2};
+        let (word_r_i, word_r_o) = self.get_radii();
+        let boundary = if inner {word_r_i} else {word_r_o};
+        if (dist(meet) > boundary) == bulge {Some(meet)} else {None}
+    }
+    //draws a letter & anything stacked on it.
+    //the word line's inside runs round the outermost b or s & its outside round the innermost, with the gaps between cut out after
+    fn draw_stack(&self, stack:&[LetterArc], mut data:(PathBuilder, PathBuilder), gaps:&mut PathBuilder, circled:&mut Vec<bool>) -> ((PathBuilder, PathBuilder), (InnerAngle,OuterAngle)) {
+        let (i_letter, o_letter) = stack_bounds(stack);
+        let end_angle = match o_letter.stem_type {
+            StemType::J => {
+                let angle = self.angle_to(o_letter.pord.as_ref());
+                (angle.into(), angle.into())
+            }
+            StemType::Z => self.draw_line_letter_arc(o_letter, &mut data),
+            StemType::B | StemType::S => {
+                let divots: Vec<&LetterArc> = stack.iter().filter(|letter| cuts_line(letter)).collect();
+                for pair in divots.windows(2) {
+                    self.nested_gap(gaps, pair[0], pair[1]);
+                }
+                self.draw_divot_arc(i_letter, o_letter, &mut data)
+            }
+        };
+        //divots are drawn by the word line, unless they're stacked on a letter that isn't
+        circled.extend(stack.iter().map(|letter| !cuts_line(letter) || !cuts_line(o_letter)));
+        (data, end_angle)
+    }
+    //b & s letters cut into the word, the line following their edges round the inside
+    fn draw_divot_arc(&self, i_letter:&LetterArc, o_letter:&LetterArc, data:&mut (PathBuilder, PathBuilder)) -> (InnerAngle,OuterAngle) {
+        let i_end_angle = self.angle_to(i_letter.pord.as_ref()) + self.letter_boundary_thi(i_letter).0;
+        let o_end_angle = self.angle_to(o_letter.pord.as_ref()) + self.letter_boundary_thi(o_letter).1;
+        let (_, i_letter_r_o) = self.get_letter_radii(i_letter);
+        let (o_letter_r_i, _) = self.get_letter_radii(o_letter);
+        self.follow_letter(&mut data.0, i_letter, i_letter_r_o, self.calc_word_arc_svg_point(i_end_angle, RadiusType::Inner), true);
+        self.follow_letter(&mut data.1, o_letter, o_letter_r_i, self.calc_word_arc_svg_point(o_end_angle, RadiusType::Outer), false);
+        (i_end_angle.into(), o_end_angle.into())
+    }
+    //z letters sit across the line, so both boundaries bulge out around the letter.
+    //the inner boundary cuts away the inside half of the letter, so its circle goes back on top
+    fn draw_line_letter_arc(&self, letter:&LetterArc, data:&mut (PathBuilder, PathBuilder)) -> (InnerAngle,OuterAngle) {
+        let angle = self.angle_to(letter.pord.as_ref());
+        let (i_thi, o_thi) = self.letter_boundary_thi(letter);
+        let (lett_r_i, lett_r_o) = self.get_letter_radii(letter);
+        self.follow_letter(&mut data.0, letter, lett_r_i, self.calc_word_arc_svg_point(angle + i_thi, RadiusType::Inner), true);
+        self.follow_letter(&mut data.1, letter, lett_r_o, self.calc_word_arc_svg_point(angle + o_thi, RadiusType::Outer), false);
+        ((angle + i_thi).into(), (angle + o_thi).into())
+    }
+    //follows a letter's edge from the end of the path to `to`, round whichever side of the word line the letter takes
+    fn follow_letter(&self, path:&mut PathBuilder, letter:&LetterArc, radius:f32, to:SvgPosition, inner:bool) {
+        let origin = self.ctx().origin();
+        let (word_r_i, word_r_o) = self.get_radii();
+        let bound = Disc::new(self.abs_svg_xy(origin).into(), if inner {word_r_i} else {word_r_o});
+        let edge = Disc::new(letter.pord.abs_svg_xy(origin).into(), radius);
+        let bulge = letter.stem_type == StemType::Z;
+        let from = path.last_position().unwrap_or(to);
+        geometry::arc_through(path, &edge, from, to, |p| bound.contains(p) != bulge);
+    }
+    //the gap between a letter & a bigger one stacked on it, wherever it shows inside the word
+    fn nested_gap(&self, gaps:&mut PathBuilder, smaller:&LetterArc, bigger:&LetterArc) {
+        let origin = self.ctx().origin();
+        let (word_r_i, _) = self.get_radii();
+        let bound = Disc::new(self.abs_svg_xy(origin).into(), word_r_i);
+        let outer = Disc::new(bigger.pord.abs_svg_xy(origin).into(), self.get_letter_radii(bigger).0);
+        let inner = Disc::new(smaller.pord.abs_svg_xy(origin).into(), self.get_letter_radii(smaller).1);
+        geometry::nested_gap(gaps, &outer, &inner, &bound);
     }
     fn letter_circle_node(&self, letter:&LetterArc) -> Circle {
         let ctx = match &letter.ctx {
@@ -299,86 +606,94 @@ pub trait Word:Cartesian {
             .set("stroke-width", ctx.stroke().strokewidth())
             .set("cx", x)
             .set("cy", y)
-            .set("r", letter.radius)
+            .set("r", letter.radius*letter.pord.frame().1)
     }
     fn calc_starting_letter_angle(&self) -> (InnerAngle,OuterAngle) {
         let letter = self.get_first_letter().expect("no letters in word arc");
-        let mut i_letter_start_angle = self.angle_to(letter.pord.as_ref());
-        let mut o_letter_start_angle = i_letter_start_angle;
-        match letter.stem_type { 
-            StemType::B | StemType::S => {
-                match self.calc_letter_thi(letter) {
-                    (Some(thi1),Some(thi2),_,_,_) => {
-                        i_letter_start_angle -= thi2;
-                        o_letter_start_angle -= thi1;
-                    }
-                    (Some(thi1),_,_,_,_) => {
-                        o_letter_start_angle -= thi1;
-                    }
-                    (_,Some(thi2),_,_,_) => {
-                        i_letter_start_angle -= thi2;
-                    }
-                    _ => (),
-                }
-            },
-            _ => (),
-        }
-        (InnerAngle(i_letter_start_angle), OuterAngle(o_letter_start_angle))
+        self.stack_start_angle(std::slice::from_ref(letter))
     }
+    fn stack_start_angle(&self, stack:&[LetterArc]) -> (InnerAngle,OuterAngle) {
+        let (i_letter, o_letter) = stack_bounds(stack);
+        (
+            InnerAngle(self.angle_to(i_letter.pord.as_ref()) - self.letter_boundary_thi(i_letter).0),
+            OuterAngle(self.angle_to(o_letter.pord.as_ref()) - self.letter_boundary_thi(o_letter).1),
+        )
+    }
+    //how far either side of its centre a letter meets the (inner, outer) word boundary
+    fn letter_boundary_thi(&self, letter:&LetterArc) -> (f32,f32) {
+        let thi = self.calc_letter_thi(letter);
+        let (i_thi, o_thi) = match letter.stem_type {
+            StemType::J => (None, None),
+            StemType::B | StemType::S => (thi.inner, thi.outer),
+            StemType::Z => (thi.inner_boundary, thi.outer_boundary),
+        };
+        (i_thi.unwrap_or(0.0), o_thi.unwrap_or(0.0))
+    }
+    //angle is in the word's own frame, like the ones angle_to gives
     fn calc_word_arc_svg_point(&self, angle:f32, inner:RadiusType) -> SvgPosition {
-        let con = self.ctx();
-        let stroke = con.stroke();
-        let (a,b) = angle.sin_cos();
-        let (x,y) = self.abs_svg_xy(con.origin());
+        let (a,b) = (angle + self.frame().0).sin_cos();
+        let (x,y) = self.abs_svg_xy(self.ctx().origin());
+        let (i_radius, o_radius) = self.get_radii();
         //negatives cancel out
         match inner {
-            RadiusType::Inner => {
-                let i_radius = self.radius() - stroke.i_stroke();
-                SvgPosition(x + i_radius * a,  y + i_radius * b)
-            }, 
-            RadiusType::Outer => {
-                let o_radius = self.radius() + stroke.o_stroke();
-                SvgPosition(x + o_radius * a,  y + o_radius * b)
-            },
-            _ => {
-                let fudge_radius = self.radius() - stroke.i_stroke() + B_DIVOT_FUDGE_PRECISION;
-                SvgPosition(x + fudge_radius * a,  y + fudge_radius * b)
-            }
+            RadiusType::Inner => SvgPosition(x + i_radius * a,  y + i_radius * b),
+            RadiusType::Outer => SvgPosition(x + o_radius * a,  y + o_radius * b),
         }
     }
-    fn calc_letter_thi(&self, letter:&LetterArc) -> (Option<f32>, Option<f32>, Option<f32>, Option<f32>, Option<f32>) {
-        let (word_r_i, word_r_o) = self.get_radii();
-        let (lett_r_i, lett_r_o) = self.get_letter_radii(letter);
-        let (word_r_i_sq,word_r_o_sq,lett_r_i_sq,lett_r_o_sq) = (word_r_i.powi(2),word_r_o.powi(2),lett_r_i.powi(2),lett_r_o.powi(2));
+    fn calc_letter_thi(&self, letter:&LetterArc) -> LetterThi {
         let dist_sq = self.pord().dist_to_sq(letter.pord.as_ref());
-        //"outer thi"
-        let thi1 = cos_rule_angle_c(dist_sq,word_r_o_sq,lett_r_i_sq);
-        //"inner thi"
-        let thi2 = cos_rule_angle_c(dist_sq,word_r_i_sq,lett_r_o_sq);
-        //inner word boundary thi
-        let thi3 = cos_rule_angle_c(dist_sq,word_r_i_sq,lett_r_i_sq);
-        //outer word boundary thi
-        let thi4 = cos_rule_angle_c(dist_sq,word_r_o_sq,lett_r_o_sq);
-        //theta
-        let theta = cos_rule_angle_c(dist_sq,lett_r_o_sq, word_r_i_sq);
-        (thi1,thi2,thi3,thi4,theta)
+        geometry::letter_thi(dist_sq, self.get_radii(), self.get_letter_radii(letter))
     }
+    //both edges are the centre line when drawing for a plotter.
+    //these are on the page, so scaled by whatever the pords are anchored on
     fn get_letter_radii(&self, letter:&LetterArc) -> (f32,f32) {
+        let radius = letter.radius*letter.pord.frame().1;
+        if self.ctx().centre_lines() {
+            return (radius, radius)
+        }
         let con = match &letter.ctx {
             None => self.ctx(),
             Some(con) => con.clone()
         };
         let stroke = con.stroke();
-        (letter.radius - stroke.i_stroke(), letter.radius + stroke.o_stroke())
+        (radius - stroke.i_stroke(), radius + stroke.o_stroke())
+    }
+    //how far out the word line reaches, stroke & all
+    fn outer_edge(&self) -> f32 {
+        let ctx = self.ctx();
+        if ctx.centre_lines() || !self.draws_path() {
+            self.radius()*self.frame().1 + ctx.stroke().strokewidth()/2.0
+        } else {
+            self.get_radii().1
+        }
     }
     fn get_radii(&self) -> (f32,f32) {
         let con = self.ctx();
+        let radius = self.radius()*self.frame().1;
+        if con.centre_lines() {
+            return (radius, radius)
+        }
         let stroke = con.stroke();
-        (self.radius() - stroke.i_stroke(),self.radius() + stroke.o_stroke())
+        (radius - stroke.i_stroke(),radius + stroke.o_stroke())
     }
 }
 
 impl Word for WordCircle {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn id(&self) -> String {
+        self.id.clone().unwrap_or_else(|| word_id(&self.name))
+    }
+    fn set_id(&mut self, id:&str) {
+        self.id = Some(id.to_string());
+    }
+    fn transliteration(&self) -> Option<&str> {
+        self.transliteration.as_deref()
+    }
+    fn set_transliteration(&mut self, transliteration:&str) {
+        self.transliteration = Some(transliteration.to_string());
+    }
     fn pord(&self) -> Rc<PordOrCord> {
         self.pord.clone()
     }
@@ -388,44 +703,55 @@ impl Word for WordCircle {
     fn arcs(&mut self) -> &mut Vec<LetterArc> {
         &mut self.arcs
     }
+    fn vowels(&mut self) -> &mut Vec<Vowel> {
+        &mut self.vowels
+    }
+    fn letter_arcs(&self) -> &[LetterArc] {
+        &self.arcs
+    }
+    fn loose_vowels(&self) -> &[Vowel] {
+        &self.vowels
+    }
     fn radius(&self) -> f32 {
         self.radius
     }
     fn get_last_letter(&self) -> Option<&LetterArc> {
-        Some(self.arcs.last()?)
+        self.arcs.last()
     }
     fn get_first_letter(&self) -> Option<&LetterArc> {
-        Some(self.arcs.first()?)
+        self.arcs.first()
     }
     fn sorted(&mut self) -> &mut bool {
         &mut self.sorted
     }
     fn new_letter(&mut self, pord:Rc<PordOrCord>,radius:f32,stem_type:StemType,ctx:Option<Context>) -> Weak<PordOrCord> {
-        if let Some(last_lett) = self.get_last_letter() {
-            if self.sorted {
-                let angle = self.angle_to(pord.as_ref());
-                let last_angle = self.angle_to(last_lett.pord().as_ref());
-                if angle < last_angle {
-                    self.sorted = false
-                }
+        if let Some(last_lett) = self.get_last_letter()
+            && self.sorted {
+            let angle = self.angle_to(pord.as_ref());
+            let last_angle = self.angle_to(last_lett.pord().as_ref());
+            if angle < last_angle {
+                self.sorted = false
             }
         }
         let letter = LetterArc::new(pord.clone(),radius,stem_type,ctx);
         self.arcs().push(letter);
-        if stem_type == StemType::S || stem_type == StemType::B {
+        if stem_type != StemType::J {
             self.path_circle = true;
         }
         Rc::downgrade(&pord)
     }
     fn draw(mut self,doc:Document) -> Document {
         println!("drawing {}...",self.name);
+        self.report_anchors();
+        let mark = doc.get_children().len();
         let xy = self.pord.abs_svg_xy(self.default_ctx.origin());
-        if !self.path_circle {
+        let doc = if !self.path_circle {
             self.draw_circle_only(doc, xy.0, xy.1)
         } else {
             self.sort_letters();
             self.word_arc_loop(doc)
-        }
+        };
+        self.group_word(doc, mark)
     }
     fn start_path_data(&self, angle:(InnerAngle, OuterAngle)) -> (PathBuilder, PathBuilder) {
         let mut o_data = utils::PathBuilder::new();
@@ -434,24 +760,41 @@ impl Word for WordCircle {
         o_data.move_to(self.calc_word_arc_svg_point(angle.1.0, RadiusType::Outer));
         (i_data,o_data)
     }
-    fn end_path_data(&self, doc:Document, data:(PathBuilder, PathBuilder)) -> Document {
-        let i_word_arc = Path::new()
-            .set("d", data.0.build_data().close())
-            .set("fill", self.ctx().colour().bg())
-            .set("stroke", "none")
-            .set("stroke-width", 0.0);
-        let o_word_arc = Path::new()
-            .set("d", data.1.build_data().close())
-            .set("fill", self.ctx().colour().stroke())
-            .set("stroke", "none")
-            .set("stroke-width", 0.0);
-        doc.add(o_word_arc).add(i_word_arc)
+    fn end_path_data(&self, doc:Document, data:(PathBuilder, PathBuilder), gaps:PathBuilder) -> Document {
+        //the inner boundary & gaps cut holes in the outer one, so whatever's behind shows through
+        let (i_path, o_path) = data;
+        let mut word_data = o_path.build_data().close();
+        if !self.ctx().centre_lines() {
+            word_data = i_path.apphend(word_data).close();
+        }
+        doc.add(self.ring_node(gaps.apphend(word_data)))
+    }
+    fn draws_path(&self) -> bool {
+        self.path_circle
+    }
+    fn closed(&self) -> bool {
+        true
     }
     fn default_word_start_angle(&self) -> f32 {0.0}
     fn default_word_end_angle(&self) -> f32 {2.0*PI}
 }
 
 impl Word for WordArc {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn id(&self) -> String {
+        self.id.clone().unwrap_or_else(|| word_id(&self.name))
+    }
+    fn set_id(&mut self, id:&str) {
+        self.id = Some(id.to_string());
+    }
+    fn transliteration(&self) -> Option<&str> {
+        self.transliteration.as_deref()
+    }
+    fn set_transliteration(&mut self, transliteration:&str) {
+        self.transliteration = Some(transliteration.to_string());
+    }
     fn pord(&self) -> Rc<PordOrCord> {
         self.pord.clone()
     }
@@ -461,31 +804,48 @@ impl Word for WordArc {
     fn arcs(&mut self) -> &mut Vec<LetterArc> {
         &mut self.arcs
     }
+    fn vowels(&mut self) -> &mut Vec<Vowel> {
+        &mut self.vowels
+    }
+    fn letter_arcs(&self) -> &[LetterArc] {
+        &self.arcs
+    }
+    fn loose_vowels(&self) -> &[Vowel] {
+        &self.vowels
+    }
     fn radius(&self) -> f32 {
         self.radius
     }
     fn get_last_letter(&self) -> Option<&LetterArc> {
-        Some(self.arcs.last()?)
+        self.arcs.last()
     }
     fn get_first_letter(&self) -> Option<&LetterArc> {
-        Some(self.arcs.first()?)
+        self.arcs.first()
     }
     fn sorted(&mut self) -> &mut bool {
         &mut self.sorted
     }
     fn new_letter(&mut self, pord:Rc<PordOrCord>,radius:f32,stem_type:StemType,ctx:Option<Context>) -> Weak<PordOrCord> {
+        match self.try_new_letter(pord, radius, stem_type, ctx) {
+            Ok(loc) => loc,
+            Err(err) => {
+                println!("bad angle: {}", err);
+                panic!()
+            }
+        }
+    }
+    fn try_new_letter(&mut self, pord:Rc<PordOrCord>,radius:f32,stem_type:StemType,ctx:Option<Context>) -> Result<Weak<PordOrCord>, GallifreyanError> {
         let angle = self.angle_to(pord.as_ref());
+        let out_of_range = GallifreyanError::AngleOutOfRange{angle, min:self.start_angle(), max:self.end_angle()};
         if angle < self.start_angle() {
-            println!("bad angle: {} - too low {}", angle, self.start_angle());
-            panic!()            
+            return Err(out_of_range)
         }
         if angle > self.end_angle() && angle < self.start_angle() + PI*2.0 {
-            println!("bad angle: {} - too high {}", angle, self.end_angle());
-            panic!()
+            return Err(out_of_range)
         }
         let letter = LetterArc::new(pord.clone(),radius,stem_type,ctx);
         self.arcs().push(letter);
-        Rc::downgrade(&pord)
+        Ok(Rc::downgrade(&pord))
     }
     fn start_path_data(&self, angle:(InnerAngle,OuterAngle)) -> (PathBuilder, PathBuilder) {
         let mut o_data = PathBuilder::new();
@@ -511,7 +871,7 @@ impl Word for WordArc {
         );
         (i_data,o_data)
     }
-    fn end_path_data(&self, doc:Document, data:(PathBuilder, PathBuilder)) -> Document {
+    fn end_path_data(&self, doc:Document, data:(PathBuilder, PathBuilder), gaps:PathBuilder) -> Document {
         let (i_rad,o_rad) = self.get_radii();
         let rad = 2.0*o_rad - i_rad;
         let end_xy = self.calc_word_arc_svg_point(self.end_angle()+self.arc_tip_length, RadiusType::Outer);
@@ -529,18 +889,24 @@ impl Word for WordArc {
             SweepDirection(false)
         );
         let mut o_data = o_path.build_data();
-        o_data = i_path.reverse_and_apphend(o_data);
-        let o_word_arc = Path::new()
-            .set("d", o_data.close())
-            .set("fill", self.ctx().colour().stroke())
-            .set("stroke", "none")
-            .set("stroke-width", 0.0);
-        doc.add(o_word_arc)
+        if !self.ctx().centre_lines() {
+            o_data = i_path.reverse_and_apphend(o_data).close();
+        }
+        doc.add(self.ring_node(gaps.apphend(o_data)))
     }
     fn draw(mut self,doc:Document) -> Document {
         println!("drawing {}...",self.name);
+        self.report_anchors();
+        let mark = doc.get_children().len();
         self.sort_letters();
-        self.word_arc_loop(doc)
+        let doc = self.word_arc_loop(doc);
+        self.group_word(doc, mark)
+    }
+    fn draws_path(&self) -> bool {
+        true
+    }
+    fn closed(&self) -> bool {
+        false
     }
     fn default_word_start_angle(&self) -> f32 {
         self.start_angle()
@@ -554,27 +920,29 @@ impl WordCircle {
     pub fn new(name:&str, pord:Rc<PordOrCord>, radius:f32,ctx:Context) -> WordCircle {
         WordCircle { 
             name: name.to_string(), 
+            id: None,
+            transliteration: None,
             pord, 
             radius, 
             arcs: Vec::new(), 
+            vowels: Vec::new(),
             default_ctx: ctx,
             path_circle:false,
             sorted:true,
         }
     }
-    fn draw_circle_only(self, mut doc: Document, word_x:f32, word_y:f32) ->Document {
+    fn draw_circle_only(&mut self, mut doc: Document, word_x:f32, word_y:f32) ->Document {
         let w_circle = Circle::new()
+            .set("id", format!("{}-ring", self.id()))
             .set("fill", self.default_ctx.colour().fill())
             .set("stroke", self.default_ctx.colour().stroke())
             .set("stroke-width", self.default_ctx.stroke().strokewidth())
             .set("cx", word_x)
             .set("cy", word_y)
-            .set("r", self.radius);
+            .set("r", self.radius*self.frame().1);
         doc = doc.add(w_circle);
-        for letter in &self.arcs {
-            doc = doc.add(self.letter_circle_node(letter));
-        }
-        doc
+        let circled = vec![true; self.arcs.len()];
+        self.draw_letters(doc, &circled)
     }
 }
 
@@ -582,9 +950,12 @@ impl WordArc {
     pub fn new(name:&str, pord:Rc<PordOrCord>, radius:f32, start_angle:f32, end_angle:f32, arc_tip_length:f32, ctx:Context) -> WordArc {
         WordArc { 
             name: name.to_string(), 
+            id: None,
+            transliteration: None,
             pord, 
             radius, 
             arcs: Vec::new(), 
+            vowels: Vec::new(),
             start_angle,
             end_angle,
             arc_tip_length,
@@ -600,6 +971,37 @@ impl WordArc {
     }
 }
 
+impl Bounded for WordCircle {
+    fn bounding_box(&self) -> BBox {
+        let line = BBox::around(self.abs_svg_xy(self.default_ctx.origin()).into(), self.outer_edge());
+        letter_bounds(self, line, &self.arcs, &self.vowels)
+    }
+}
+
+impl Bounded for WordArc {
+    //the ends of the arc & wherever it passes straight up, down or across
+    fn bounding_box(&self) -> BBox {
+        let (start, end) = (self.start_angle - self.arc_tip_length, self.end_angle + self.arc_tip_length);
+        let rotation = self.frame().0;
+        let quarters = ((start + rotation)/FRAC_PI_2).ceil() as i32..=((end + rotation)/FRAC_PI_2).floor() as i32;
+        let angles = [start, end].into_iter().chain(quarters.map(|i| i as f32*FRAC_PI_2 - rotation));
+        let (word_r_i, word_r_o) = self.get_radii();
+        let (x, y) = self.abs_svg_xy(self.default_ctx.origin());
+        let points = angles.map(|angle| {
+            let (sin, cos) = (angle + rotation).sin_cos();
+            SvgPosition(x + word_r_o*sin, y + word_r_o*cos)
+        });
+        let line = BBox::of_points(points, self.outer_edge() - word_r_o).expect("there's always the ends");
+        //the inside edge only matters at the ends, the rest of it is further in
+        let inner_ends = [start, end].map(|angle| {
+            let (sin, cos) = (angle + rotation).sin_cos();
+            SvgPosition(x + word_r_i*sin, y + word_r_i*cos)
+        });
+        let line = BBox::of_points(inner_ends, 0.0).map_or(line, |ends| line.union(&ends));
+        letter_bounds(self, line, &self.arcs, &self.vowels)
+    }
+}
+
 impl Cartesian for WordCircle {
     fn rel_xy(&self) -> (f32,f32) {
         self.pord().rel_xy()
@@ -607,6 +1009,9 @@ impl Cartesian for WordCircle {
     fn abs_svg_xy(&self, svg_origin:(f32,f32)) -> (f32,f32) {
         self.pord().abs_svg_xy(svg_origin)
     }
+    fn frame(&self) -> (f32,f32) {
+        self.pord().frame()
+    }
 }
 
 impl Cartesian for WordArc {
@@ -616,6 +1021,9 @@ impl Cartesian for WordArc {
     fn abs_svg_xy(&self, svg_origin:(f32,f32)) -> (f32,f32) {
         self.pord().abs_svg_xy(svg_origin)
     }
+    fn frame(&self) -> (f32,f32) {
+        self.pord().frame()
+    }
 }
 
 impl LetterArc {
@@ -624,24 +1032,47 @@ impl LetterArc {
             pord,
             radius, 
             stem_type, 
-            ctx
+            ctx,
+            vowel: None,
+            decoration: Decoration::None,
+            text: None,
         }
     }
-    fn pord(&self) -> Rc<PordOrCord> {
+    pub fn pord(&self) -> Rc<PordOrCord> {
         self.pord.clone()
     }
-}
-
-fn cos_rule_angle_c(a_dist_sq:f32,b_dist_sq:f32,c_dist_sq:f32) -> Option<f32> {
-    let top = a_dist_sq + b_dist_sq - c_dist_sq;
-    let bot = a_dist_sq.sqrt()*(b_dist_sq.sqrt())*2.0;
-    thi_check(top, bot)
-}
-
-fn thi_check(top:f32,bot:f32) -> Option<f32> {
-    if top.abs() <= bot {
-        Some((top/bot).acos())
-    } else {None}
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+    pub fn stem_type(&self) -> StemType {
+        self.stem_type
+    }
+    pub fn vowel(&self) -> Option<&Vowel> {
+        self.vowel.as_ref()
+    }
+    //replaces any vowel already there
+    pub fn set_vowel(&mut self, vowel:Vowel) {
+        self.vowel = Some(vowel);
+    }
+    pub fn take_vowel(&mut self) -> Option<Vowel> {
+        self.vowel.take()
+    }
+    pub fn decoration(&self) -> Decoration {
+        self.decoration
+    }
+    pub fn set_decoration(&mut self, decoration:Decoration) {
+        self.decoration = decoration;
+    }
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+    pub fn set_text(&mut self, text:&str) {
+        self.text = Some(text.to_string());
+    }
+    //a vowel on or in the letter, so dots & lines keep clear of the middle
+    pub(crate) fn crowded(&self) -> bool {
+        self.vowel.as_ref().is_some_and(|vowel| vowel.kind() != VowelKind::A)
+    }
 }
 
 impl From<f32> for InnerAngle {
@@ -653,4 +1084,77 @@ impl From<f32> for OuterAngle {
     fn from(value: f32) -> Self {
         OuterAngle(value)
     }
-}
\ No newline at end of file
+}
+
+//adds the letters & vowels to the word line's box.
+//divots stay inside the word line when it's drawn as a path, anything else can stick out
+fn letter_bounds<W:Word>(word:&W, line:BBox, letters:&[LetterArc], vowels:&[Vowel]) -> BBox {
+    let (word_pord, word_ctx) = (word.pord(), word.ctx());
+    let mut bounds = line;
+    for letter in letters {
+        let ctx = letter.ctx.as_ref().unwrap_or(&word_ctx);
+        if !word.draws_path() || !cuts_line(letter) {
+            bounds = bounds.union(&basic::circle_bounds(letter.pord.as_ref(), letter.radius*letter.pord.frame().1, ctx));
+        }
+        for line in word.letter_line_stubs(letter) {
+            bounds = bounds.union(&line.bounding_box());
+        }
+        if let Some(vowel) = &letter.vowel {
+            bounds = bounds.union(&vowel.bounding_box(&word_pord, &word_ctx));
+        }
+    }
+    for vowel in vowels {
+        bounds = bounds.union(&vowel.bounding_box(&word_pord, &word_ctx));
+    }
+    bounds
+}
+
+//splits sorted letters into stacks, every letter after the first sharing the centre of, or hanging off, the one before
+fn stacks(arcs:&[LetterArc]) -> Vec<&[LetterArc]> {
+    let mut stacks = Vec::new();
+    let mut start = 0;
+    for i in 1..arcs.len() {
+        let prev = arcs[i - 1].pord();
+        let stacked = Rc::ptr_eq(&prev, &arcs[i].pord)
+            || arcs[i].pord.get_anchor().is_some_and(|anchor| Weak::ptr_eq(&Rc::downgrade(&prev), &anchor));
+        if !stacked {
+            stacks.push(&arcs[start..i]);
+            start = i;
+        }
+    }
+    if start < arcs.len() {
+        stacks.push(&arcs[start..]);
+    }
+    stacks
+}
+
+fn word_id(name:&str) -> String {
+    let slug = utils::id_slug(name);
+    if slug.is_empty() {"word".to_string()} else {slug}
+}
+
+//letters & vowels are lettered so they can't be mistaken for a numbered repeat of the word
+fn letter_id(word_id:&str, i:usize) -> String {
+    format!("{}-l{}", word_id, i)
+}
+
+fn vowel_id(word_id:&str, i:usize) -> String {
+    format!("{}-v{}", word_id, i)
+}
+
+fn vowel_letter(kind:VowelKind) -> String {
+    format!("{:?}", kind).to_lowercase()
+}
+
+fn cuts_line(letter:&LetterArc) -> bool {
+    matches!(letter.stem_type, StemType::B | StemType::S)
+}
+
+//the letters the (inner, outer) boundaries follow, first letter must be innermost
+fn stack_bounds(stack:&[LetterArc]) -> (&LetterArc,&LetterArc) {
+    let base = &stack[0];
+    let i_letter = if cuts_line(base) {
+        stack.iter().rfind(|letter| cuts_line(letter)).unwrap_or(base)
+    } else {base};
+    (i_letter, base)
+}